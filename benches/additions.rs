@@ -15,7 +15,8 @@ fn benchmark(c: &mut Criterion) {
         computer.high_speed_memory.load(vec![
             (0o0000, inst!(A 0o0001 0o1000 0o0001 0o0000)),
             (0o1000, 1.try_into().unwrap()),
-        ]);
+        ])
+        .unwrap();
 
         b.iter(|| computer.step_once());
     });
@@ -0,0 +1,77 @@
+//! # Disassembler
+//! Turns words back into the listing format understood by
+//! [`assemble`](super::assemble).
+//!
+//! A word doesn't say whether it is an order or a number, so every word that
+//! decodes to a used order is written as one. Numbers (like the constants at the
+//! end of the example program) will often come out as nonsense orders, but they
+//! still assemble back to exactly the same bits.
+
+use crate::{core::wire::Wire, orders::Order, word::Word};
+
+/// Renders a single word, as it would appear after the placement address.
+#[must_use]
+pub fn disassemble_word(word: Word) -> String {
+    let order = Order::from(word);
+
+    if let Some(mneumonic) = order.kind.to_mneumonic() {
+        format!(
+            "{}\t{:0>4o}\t{:0>4o}\t{:0>4o}\t{:0>4o}",
+            mneumonic,
+            order.addresses[0],
+            order.addresses[1],
+            order.addresses[2],
+            order.addresses[3],
+        )
+    } else {
+        word.to_octal()
+    }
+}
+
+/// Renders a program as a listing, one line per word, in the order given.
+#[must_use]
+pub fn disassemble(program: &[(usize, Word)]) -> String {
+    program
+        .iter()
+        .map(|&(address, word)| format!("{:0>4o}\t{}\n", address, disassemble_word(word)))
+        .collect()
+}
+
+/// Renders the program recorded on a wire (see [`Wire::program`]).
+#[must_use]
+pub fn disassemble_wire(wire: &Wire) -> String {
+    disassemble(&wire.program())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::assembler::{assemble, assemble_words};
+
+    #[test]
+    fn round_trip() {
+        let listing = include_str!("../test_programs/dec_to_bin.edvac");
+        let wire = assemble(listing).unwrap();
+
+        let disassembled = disassemble_wire(&wire);
+        let reassembled = assemble(&disassembled).unwrap();
+
+        assert_eq!(wire.program(), reassembled.program());
+        assert_eq!(wire.program(), assemble_words(listing).unwrap());
+        assert_eq!(disassemble_wire(&reassembled), disassembled);
+    }
+
+    #[test]
+    fn words() {
+        assert_eq!(
+            disassemble_word(inst!(W 0o0001 0o0202 0o0000 0o1002)),
+            "W\t0001\t0202\t0000\t1002"
+        );
+        assert_eq!(
+            disassemble_word(Word::from_bits((0o120_0000_0000_0000 << 1) | 1)),
+            "-120 0000 0000 0000"
+        );
+        assert_eq!(disassemble_word(Word::from_bits(0)), "+000 0000 0000 0000");
+    }
+}
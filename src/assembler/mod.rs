@@ -1,7 +1,7 @@
 //! # EDVAC Assembler
 //! In addition to the [Rust macro](crate::order_macros) "assembler", this crate
 //! provides an assembler that takes a plain-text program listing and puts it on
-//! a [Wire] for swapping into the system.
+//! a [Wire] for swapping into the system. The [disassembler] goes the other way.
 //!
 //! ## Assembly Language
 //! The assembly language itself is simple enough that it is best explained with
@@ -21,9 +21,36 @@
 //! ### Notes
 //! Lines that don't start with an octal address are treated as comments, as are
 //! any extra characters at the end of lines. Leading spaces are ignored in this
-//! check.
+//! check. Any other line that can't be assembled is reported as an
+//! [`AssembleError`].
 //!
 //! While the above example uses tabs, spaces are also allowed.
+//!
+//! ### Labels and Constants
+//! Moving a routine around in memory would mean renumbering every operand that
+//! refers to it, so a line can be given a label by starting it with a name and
+//! a colon. Names can also be given to constants with `EQU`. Anywhere an address
+//! is expected, a name can be used instead, optionally followed by an octal
+//! offset:
+//! ```text
+//! DIGITS  EQU 1001
+//! SHIFT   EQU 0247
+//! START:  0000    E   DIGITS  SHIFT   RESULT  NEXT
+//! NEXT:   0001    H   0000    0000    0000    START
+//! RESULT: 0002    +000 0000 0000 0000
+//!         0003    A   RESULT  RESULT  RESULT+1 START
+//! ```
+//! Names start with a letter or an underscore, and are made up of letters,
+//! digits and underscores. They can be used before they are defined. A label
+//! has to be followed by an octal address (otherwise the whole line is treated
+//! as a comment, as with `TITLE:` in the first example), so raw octal listings
+//! work exactly as they did before.
+
+pub mod disassembler;
+
+pub use disassembler::{disassemble, disassemble_wire, disassemble_word};
+
+use std::{collections::HashMap, fmt};
 
 use crate::{
     core::wire::Wire,
@@ -32,61 +59,384 @@ use crate::{
     word::Word,
 };
 
-// Quick and dirty. Todo: proper error handling, rather than just ignoring lines
-pub fn assemble(listing: &str) -> Option<Wire> {
-    let assembled = listing
-        .lines()
-        .filter_map(|line| {
-            let mut split = line.split_ascii_whitespace();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssembleErrorKind {
+    /// The order type isn't one of the known mneumonics.
+    BadMnemonic,
+    /// A field that should be an octal number isn't one.
+    NotOctal,
+    /// An address doesn't fit in [`ADDRESS_WIDTH`] bits.
+    AddressOutOfRange,
+    /// A fragment of a number is too large: the first fragment holds 7 bits and
+    /// the rest hold 12.
+    FragmentOutOfRange,
+    /// The line ended before all of the addresses or fragments were given.
+    MissingOperand,
+    /// Something was already placed at this address earlier in the listing.
+    DuplicateAddress,
+    /// A label or constant was used but never defined.
+    UndefinedSymbol,
+    /// A label or constant was defined more than once.
+    DuplicateSymbol,
+    /// A constant is defined in terms of itself.
+    CircularDefinition,
+}
+
+impl fmt::Display for AssembleErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            AssembleErrorKind::BadMnemonic => "unknown order type",
+            AssembleErrorKind::NotOctal => "not an octal number",
+            AssembleErrorKind::AddressOutOfRange => "address out of range",
+            AssembleErrorKind::FragmentOutOfRange => "fragment out of range",
+            AssembleErrorKind::MissingOperand => "missing operand",
+            AssembleErrorKind::DuplicateAddress => "duplicate address",
+            AssembleErrorKind::UndefinedSymbol => "undefined symbol",
+            AssembleErrorKind::DuplicateSymbol => "duplicate symbol",
+            AssembleErrorKind::CircularDefinition => "circular definition",
+        })
+    }
+}
+
+/// A problem with a single line of a listing. Lines and columns are counted
+/// from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssembleError {
+    pub line: usize,
+    pub column: usize,
+    pub token: String,
+    pub kind: AssembleErrorKind,
+}
+
+impl AssembleError {
+    fn new(line: usize, (column, token): (usize, &str), kind: AssembleErrorKind) -> AssembleError {
+        AssembleError {
+            line,
+            column,
+            token: token.into(),
+            kind,
+        }
+    }
+}
 
-            let address = usize::from_str_radix(split.next()?, 8).ok()?;
-            if address as u64 & !ADDRESS_MASK != 0 {
-                return None;
+impl fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.token.is_empty() {
+            write!(f, "{}:{}: {}", self.line, self.column, self.kind)
+        } else {
+            write!(
+                f,
+                "{}:{}: {} `{}`",
+                self.line, self.column, self.kind, self.token
+            )
+        }
+    }
+}
+
+impl std::error::Error for AssembleError {}
+
+/// A token and the column it starts at.
+type Token<'a> = (usize, &'a str);
+
+/// Splits a line into its whitespace-separated tokens.
+fn tokenize(line: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (column, (index, c)) in line.char_indices().enumerate() {
+        match (start, c.is_ascii_whitespace()) {
+            (None, false) => start = Some((column + 1, index)),
+            (Some((start_column, start_index)), true) => {
+                tokens.push((start_column, &line[start_index..index]));
+                start = None;
             }
+            _ => {}
+        }
+    }
 
-            let next = split.next()?;
-            if ['+', '-'].contains(&next.chars().next()?) {
-                let (sign, first) = next.split_at(1);
+    if let Some((start_column, start_index)) = start {
+        tokens.push((start_column, &line[start_index..]));
+    }
 
-                let mut raw = u64::from_str_radix(first, 8).ok()?;
+    tokens
+}
 
-                if raw > 0o177 {
-                    return None;
-                }
+fn is_symbol(name: &str) -> bool {
+    let mut chars = name.chars();
 
-                for _fragment_index in 2..=4 {
-                    let next_fragment = u64::from_str_radix(split.next()?, 8).ok()?;
-                    if next_fragment > 0o7777 {
-                        return None;
-                    }
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
 
-                    raw = (raw << 12) | next_fragment;
-                }
+enum Definition<'a> {
+    Label(u64),
+    Constant(Token<'a>),
+}
 
-                Some((
-                    address,
-                    Word::from_bits((raw << 1) | (if sign == "+" { 0 } else { 1 })),
-                ))
-            } else {
-                // order
-                let kind = OrderKind::from_mneumonic(next)?.to_bits();
-
-                let mut raw: u64 = 0;
-                for _address_index in 1..=4 {
-                    let next_address = u64::from_str_radix(split.next()?, 8).ok()?;
-                    if next_address & !ADDRESS_MASK != 0 {
-                        return None;
-                    }
-
-                    raw = (raw << ADDRESS_WIDTH) | next_address;
+/// Labels and constants, gathered in the first pass over the listing so that
+/// they can be used before they are defined.
+#[derive(Default)]
+struct SymbolTable<'a> {
+    definitions: HashMap<&'a str, (usize, Definition<'a>)>,
+}
+
+impl<'a> SymbolTable<'a> {
+    fn define(
+        &mut self,
+        line: usize,
+        (column, name): Token<'a>,
+        definition: Definition<'a>,
+    ) -> Result<(), AssembleError> {
+        if self.definitions.contains_key(name) {
+            return Err(AssembleError::new(
+                line,
+                (column, name),
+                AssembleErrorKind::DuplicateSymbol,
+            ));
+        }
+
+        self.definitions.insert(name, (line, definition));
+
+        Ok(())
+    }
+
+    /// Evaluates an octal number, or a symbol optionally followed by an octal
+    /// offset (`LOOP+2`, `TABLE-1`).
+    fn evaluate(&self, line: usize, token: Token, depth: usize) -> Result<u64, AssembleError> {
+        let error = |kind| AssembleError::new(line, token, kind);
+
+        let text = token.1;
+        if !text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            return u64::from_str_radix(text, 8).map_err(|_| error(AssembleErrorKind::NotOctal));
+        }
+
+        let (name, negative, offset) = match text.find(['+', '-']) {
+            Some(index) => {
+                let (name, offset) = text.split_at(index);
+                let magnitude = u64::from_str_radix(&offset[1..], 8)
+                    .map_err(|_| error(AssembleErrorKind::NotOctal))?;
+
+                (name, offset.starts_with('-'), magnitude)
+            }
+            None => (text, false, 0),
+        };
+
+        if !is_symbol(name) {
+            return Err(error(AssembleErrorKind::UndefinedSymbol));
+        }
+
+        let value = match self.definitions.get(name) {
+            None => return Err(error(AssembleErrorKind::UndefinedSymbol)),
+            Some((_, Definition::Label(address))) => *address,
+            Some((definition_line, Definition::Constant(value))) => {
+                if depth > self.definitions.len() {
+                    return Err(error(AssembleErrorKind::CircularDefinition));
                 }
 
-                Some((address, Word::from_bits((raw << 4) | kind)))
+                self.evaluate(*definition_line, *value, depth + 1)
+                    .map_err(|inner| error(inner.kind))?
             }
-        })
-        .collect::<Vec<_>>();
+        };
+
+        let address = if negative {
+            value.checked_sub(offset)
+        } else {
+            value.checked_add(offset)
+        };
+
+        address.ok_or_else(|| error(AssembleErrorKind::AddressOutOfRange))
+    }
+}
+
+/// Parses the tokens of one line (past the placement address) into the word
+/// that they describe.
+struct LineParser<'a, 'b> {
+    line: usize,
+    end_column: usize,
+    tokens: std::vec::IntoIter<Token<'a>>,
+    symbols: &'b SymbolTable<'a>,
+}
+
+impl<'a, 'b> LineParser<'a, 'b> {
+    fn error(&self, token: Token, kind: AssembleErrorKind) -> AssembleError {
+        AssembleError::new(self.line, token, kind)
+    }
+
+    fn next(&mut self) -> Result<Token<'a>, AssembleError> {
+        self.tokens
+            .next()
+            .ok_or_else(|| self.error((self.end_column, ""), AssembleErrorKind::MissingOperand))
+    }
+
+    fn octal(&self, token: Token) -> Result<u64, AssembleError> {
+        u64::from_str_radix(token.1, 8).map_err(|_| self.error(token, AssembleErrorKind::NotOctal))
+    }
+
+    fn address(&mut self) -> Result<u64, AssembleError> {
+        let token = self.next()?;
+        let address = self.symbols.evaluate(self.line, token, 0)?;
+
+        if address & !ADDRESS_MASK != 0 {
+            Err(self.error(token, AssembleErrorKind::AddressOutOfRange))
+        } else {
+            Ok(address)
+        }
+    }
+
+    fn fragment(&mut self, token: Token, max: u64) -> Result<u64, AssembleError> {
+        let fragment = self.octal(token)?;
 
-    Some(Wire::with_program(assembled))
+        if fragment > max {
+            Err(self.error(token, AssembleErrorKind::FragmentOutOfRange))
+        } else {
+            Ok(fragment)
+        }
+    }
+
+    fn word(&mut self) -> Result<Word, AssembleError> {
+        let (column, next) = self.next()?;
+
+        if next.starts_with(['+', '-']) {
+            // number
+            let (sign, first) = next.split_at(1);
+
+            let mut raw = self.fragment((column + 1, first), 0o177)?;
+
+            for _fragment_index in 2..=4 {
+                let token = self.next()?;
+                raw = (raw << 12) | self.fragment(token, 0o7777)?;
+            }
+
+            Ok(Word::from_bits(
+                (raw << 1) | (if sign == "+" { 0 } else { 1 }),
+            ))
+        } else {
+            // order
+            let kind = OrderKind::from_mneumonic(next)
+                .ok_or_else(|| self.error((column, next), AssembleErrorKind::BadMnemonic))?
+                .to_bits();
+
+            let mut raw: u64 = 0;
+            for _address_index in 1..=4 {
+                raw = (raw << ADDRESS_WIDTH) | self.address()?;
+            }
+
+            Ok(Word::from_bits((raw << 4) | kind))
+        }
+    }
+}
+
+/// A line that places a word in memory, waiting on the second pass.
+struct Placement<'a> {
+    line: usize,
+    end_column: usize,
+    address: Token<'a>,
+    tokens: Vec<Token<'a>>,
+}
+
+/// Assembles a listing into the words it describes, paired with the addresses
+/// they are to be placed at, in the order they appear in the listing.
+pub fn assemble_words(listing: &str) -> Result<Vec<(usize, Word)>, Vec<AssembleError>> {
+    let mut errors = Vec::new();
+    let mut symbols = SymbolTable::default();
+    let mut placements = Vec::new();
+
+    // first pass: find every label and constant
+    for (index, line) in listing.lines().enumerate() {
+        let line_number = index + 1;
+        let mut tokens = tokenize(line);
+
+        let defined = match tokens.as_slice() {
+            [name, (_, "EQU"), value, ..] if is_symbol(name.1) => {
+                symbols.define(line_number, *name, Definition::Constant(*value))
+            }
+            [(column, label), (_, address), ..]
+                if label.strip_suffix(':').is_some_and(is_symbol)
+                    && u64::from_str_radix(address, 8).is_ok() =>
+            {
+                let label = (*column, &label[..label.len() - 1]);
+                let address = u64::from_str_radix(address, 8).unwrap();
+
+                tokens.remove(0);
+                symbols.define(line_number, label, Definition::Label(address))
+            }
+            _ => Ok(()),
+        };
+
+        if let Err(error) = defined {
+            errors.push(error);
+        }
+
+        // lines that don't start with an octal address are comments
+        if tokens.is_empty() || u64::from_str_radix(tokens[0].1, 8).is_err() {
+            continue;
+        }
+
+        let address = tokens.remove(0);
+        placements.push(Placement {
+            line: line_number,
+            end_column: line.chars().count() + 1,
+            address,
+            tokens,
+        });
+    }
+
+    // check that constants make sense, even if they're never used
+    for (line, definition) in symbols.definitions.values() {
+        if let Definition::Constant(value) = definition {
+            if let Err(error) = symbols.evaluate(*line, *value, 0) {
+                errors.push(error);
+            }
+        }
+    }
+
+    // second pass: assemble every line, now that all symbols are known
+    let mut words = Vec::new();
+    let mut placed = HashMap::new();
+
+    for placement in placements {
+        let mut parser = LineParser {
+            line: placement.line,
+            end_column: placement.end_column,
+            tokens: placement.tokens.into_iter(),
+            symbols: &symbols,
+        };
+
+        let address = parser.octal(placement.address).unwrap() as usize;
+
+        if address as u64 & !ADDRESS_MASK != 0 {
+            errors.push(parser.error(placement.address, AssembleErrorKind::AddressOutOfRange));
+            continue;
+        }
+
+        if placed.insert(address, placement.line).is_some() {
+            errors.push(parser.error(placement.address, AssembleErrorKind::DuplicateAddress));
+            continue;
+        }
+
+        match parser.word() {
+            Ok(word) => words.push((address, word)),
+            Err(error) => errors.push(error),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(words)
+    } else {
+        errors.sort_by_key(|error| (error.line, error.column));
+        errors.dedup();
+
+        Err(errors)
+    }
+}
+
+/// Assembles a listing onto a [Wire], in the format read by the "read fifth
+/// address" wire order. Every problem found in the listing is returned, rather
+/// than just the first.
+pub fn assemble(listing: &str) -> Result<Wire, Vec<AssembleError>> {
+    assemble_words(listing).map(Wire::with_program)
 }
 
 #[cfg(test)]
@@ -152,9 +502,108 @@ mod tests {
 
         for (address, word) in words {
             assert_eq!(wire.read_address(), address);
-            wire.translate(WireShift::Forward(ADDRESS_WIDTH));
+            wire.translate(WireShift::Forward(ADDRESS_WIDTH)).unwrap();
             assert_eq!(wire.read_word(), word);
-            wire.translate(WireShift::Forward(BIT_WIDTH));
+            wire.translate(WireShift::Forward(BIT_WIDTH)).unwrap();
+        }
+    }
+
+    #[test]
+    fn assemble_errors() {
+        let listing = "\
+0000	E	1001	0003	1003	1003
+0001	X	1001	0003	1003	1003
+0002	A	1001	2003	1003	1003
+0003	+200 0000 0000 0000
+0004	+000 0000 10000 0000
+0005	A	1001	0003
+0000	+000 0000 0000 0000
+2000	H	0000	0000	0000	0000
+0006	C	1001	00x3	1003	1003
+This line is a comment.
+";
+
+        let errors = assemble_words(listing).unwrap_err();
+
+        let expected = [
+            (2, 6, "X", AssembleErrorKind::BadMnemonic),
+            (3, 13, "2003", AssembleErrorKind::AddressOutOfRange),
+            (4, 7, "200", AssembleErrorKind::FragmentOutOfRange),
+            (5, 16, "10000", AssembleErrorKind::FragmentOutOfRange),
+            (6, 17, "", AssembleErrorKind::MissingOperand),
+            (7, 1, "0000", AssembleErrorKind::DuplicateAddress),
+            (8, 1, "2000", AssembleErrorKind::AddressOutOfRange),
+            (9, 13, "00x3", AssembleErrorKind::NotOctal),
+        ];
+
+        assert_eq!(errors.len(), expected.len());
+        for (error, (line, column, token, kind)) in errors.iter().zip(expected) {
+            assert_eq!(
+                *error,
+                AssembleError {
+                    line,
+                    column,
+                    token: token.into(),
+                    kind
+                }
+            );
         }
     }
+
+    #[test]
+    fn assemble_symbols() {
+        let symbolic = "\
+DIGITS  EQU 1001
+SHIFT   EQU 0247
+START:  0000    E   DIGITS  SHIFT   RESULT  NEXT
+NEXT:   0001    H   0000    0000    0000    START
+RESULT: 0002    +000 0000 0000 0000
+        0003    A   RESULT  RESULT  RESULT+1 NEXT-1
+";
+        let raw = "\
+0000    E   1001    0247    0002    0001
+0001    H   0000    0000    0000    0000
+0002    +000 0000 0000 0000
+0003    A   0002    0002    0003    0000
+";
+
+        assert_eq!(
+            assemble_words(symbolic).unwrap(),
+            assemble_words(raw).unwrap()
+        );
+    }
+
+    #[test]
+    fn symbol_errors() {
+        let listing = "\
+A       EQU B
+B       EQU A
+LOOP:   0000    H   0000    0000    0000    NOWHERE
+LOOP:   0001    H   0000    0000    0000    LOOP
+C       EQU 7777
+        0002    H   0000    0000    0000    C
+BIG     EQU 777777777777777777777
+        0003    H   0000    0000    0000    BIG+1
+        0004    H   0000    0000    0000    LOOP-1000000000000000000000
+";
+
+        let errors = assemble_words(listing).unwrap_err();
+        let kinds: Vec<_> = errors
+            .iter()
+            .map(|error| (error.line, error.kind))
+            .collect();
+
+        assert_eq!(
+            kinds,
+            [
+                (1, AssembleErrorKind::CircularDefinition),
+                (2, AssembleErrorKind::CircularDefinition),
+                (3, AssembleErrorKind::UndefinedSymbol),
+                (4, AssembleErrorKind::DuplicateSymbol),
+                (6, AssembleErrorKind::AddressOutOfRange),
+                (8, AssembleErrorKind::AddressOutOfRange),
+                (9, AssembleErrorKind::AddressOutOfRange),
+            ]
+        );
+    }
 }
@@ -39,6 +39,8 @@ pub struct App {
     special_order: special_order_input::SpecialOrderInput,
 
     program_loader: program_loader::ProgramLoader,
+
+    typewriter_output: typewriter_output::TypewriterOutput,
 }
 
 #[derive(Debug, Clone)]
@@ -52,6 +54,7 @@ pub enum Message {
     AddressB(address_input::Message),
     SpecialOrder(special_order_input::Message),
     ProgramLoad(program_loader::Message),
+    TypewriterOutput(typewriter_output::Message),
 }
 
 impl Sandbox for App {
@@ -81,6 +84,8 @@ impl Sandbox for App {
             special_order: special_order_input::SpecialOrderInput::default(),
 
             program_loader: program_loader::ProgramLoader::default(),
+
+            typewriter_output: typewriter_output::TypewriterOutput::default(),
         }
     }
 
@@ -112,6 +117,9 @@ impl Sandbox for App {
                 button_panels::Message::Halt => {
                     self.computer.send(EdvacMessage::Halt);
                 }
+                button_panels::Message::StepBack => {
+                    self.computer.send(EdvacMessage::StepBack);
+                }
 
                 _ => {} // unimplemented
             },
@@ -146,10 +154,23 @@ impl Sandbox for App {
                         self.special_order.update(m),
                     )));
             }
-            Message::ProgramLoad(m) => {
-                if let Some((spool, wire)) = self.program_loader.update(m) {
+            Message::ProgramLoad(m) => match self.program_loader.update(m) {
+                Some(program_loader::Action::Load(spool, wire)) => {
                     self.computer.send(EdvacMessage::LoadWire(spool, wire));
                 }
+                Some(program_loader::Action::Save(spool, path)) => {
+                    self.computer.send(EdvacMessage::SaveWire(spool, path));
+                }
+                None => {}
+            },
+            Message::TypewriterOutput(m) => {
+                if let typewriter_output::Message::Clear = m {
+                    self.computer.clear_typewriter();
+                }
+
+                if let Some(mode) = self.typewriter_output.update(m) {
+                    self.computer.send(EdvacMessage::TypewriterMode(mode));
+                }
             }
         };
     }
@@ -214,7 +235,15 @@ impl Sandbox for App {
             .push(
                 Column::new()
                     .spacing(20)
-                    .push(self.program_loader.view().map(Message::ProgramLoad)),
+                    .push(self.program_loader.view().map(Message::ProgramLoad))
+                    .push(
+                        Container::new(
+                            self.typewriter_output
+                                .view(&self.computer.typewriter_output())
+                                .map(Message::TypewriterOutput),
+                        )
+                        .style(ContainerStyle),
+                    ),
             )
             .into()
     }
@@ -0,0 +1,291 @@
+//! # Arithmetic checking
+//! The EDVAC was built with two arithmetic units doing every order side by
+//! side, and a circuit comparing their results that stopped the machine when
+//! they disagreed, so that a failing tube couldn't quietly spoil a computation.
+//! This emulator can check itself the same way: with
+//! [`crate::Edvac::arithmetic_check`] set, the `C`, `A`, `S`, `M`, `m`, `D` and
+//! `d` orders are also worked out by the reference implementations here, and if
+//! the two don't agree the machine faults with
+//! [`EdvacError::ArithmeticCheckFailure`] before the result is stored.
+//!
+//! [`Word`] does its arithmetic on the value of a word as a native integer. The
+//! reference implementations never do, and instead work long-hand on the sign
+//! bit and the 43-bit magnitude of each word: adding or subtracting magnitudes
+//! depending on the signs, multiplying by adding in the multiplicand for each
+//! bit of the multiplier, and dividing by subtracting the divisor for each bit
+//! of the quotient. Where the machine doesn't give a plain number they follow
+//! the same rules as [`Word`]:
+//! - a sum or difference that is too big keeps its sign, and its magnitude is
+//!   what's left over after the largest one;
+//! - -0 is read as 0, and a result of zero is always +0, even for either
+//!   half of a product or quotient (see [`crate::word`]);
+//! - a quotient of one or more, including dividing by ±0, overflows, leaving
+//!   the largest magnitude in both words with the sign of the quotient.
+//!
+//! This is a check on the emulator rather than an emulation of the checking
+//! circuit, so a machine that faults this way has found a bug here. Since the
+//! reference implementations follow the same rules, checking only catches a
+//! result that was worked out wrong: if a rule above isn't what the EDVAC did,
+//! both sides get it wrong the same way and nothing faults.
+//!
+//! ## Backends
+//! Which implementation the machine actually uses is up to
+//! [`crate::Edvac::arithmetic_backend`]: [`Word`]'s own arithmetic, or the
+//! bit-serial arithmetic of [`crate::serial`], which works the way the
+//! machine's arithmetic unit did. Either way, checking compares it against the
+//! reference implementations here.
+
+use crate::{
+    error::EdvacError,
+    orders::OrderKind,
+    serial,
+    word::{Word, U43_MAX},
+    Edvac,
+};
+
+use std::fmt::Debug;
+
+use log::error;
+
+/// -0 is read as 0.
+fn is_negative(word: Word) -> bool {
+    word.get_bits() & 0b1 == 0b1 && magnitude(word) != 0
+}
+
+fn magnitude(word: Word) -> u64 {
+    word.get_bits() >> 1
+}
+
+/// Zero is only encoded as +0.
+fn word(negative: bool, magnitude: u64) -> Word {
+    Word::from_bits(magnitude << 1 | u64::from(negative && magnitude != 0))
+}
+
+/// Reference version of [`Word::overflowing_add`].
+#[must_use]
+pub fn overflowing_add(a: Word, b: Word) -> (Word, bool) {
+    let (negative, sum) = if is_negative(a) == is_negative(b) {
+        (is_negative(a), magnitude(a) + magnitude(b))
+    } else if magnitude(a) >= magnitude(b) {
+        (is_negative(a), magnitude(a) - magnitude(b))
+    } else {
+        (is_negative(b), magnitude(b) - magnitude(a))
+    };
+
+    if sum > U43_MAX {
+        (word(negative, sum - U43_MAX), true)
+    } else {
+        (word(negative, sum), false)
+    }
+}
+
+/// Reference version of [`Word::overflowing_sub`].
+#[must_use]
+pub fn overflowing_sub(a: Word, b: Word) -> (Word, bool) {
+    overflowing_add(a, word(!is_negative(b), magnitude(b)))
+}
+
+/// Reference version of [`Word::mul`].
+#[must_use]
+pub fn mul(a: Word, b: Word) -> (Word, Word) {
+    let multiplicand = u128::from(magnitude(a));
+    let multiplier = magnitude(b);
+
+    let mut product = 0_u128;
+    for bit in (0..43).rev() {
+        product <<= 1;
+        if (multiplier >> bit) & 0b1 == 0b1 {
+            product += multiplicand;
+        }
+    }
+
+    let negative = is_negative(a) != is_negative(b);
+
+    (
+        word(negative, (product >> 43) as u64),
+        word(negative, product as u64 & U43_MAX),
+    )
+}
+
+/// Reference version of [`Word::overflowing_div`].
+#[must_use]
+pub fn overflowing_div(a: Word, b: Word) -> (Word, Word, bool) {
+    let dividend = magnitude(a);
+    let divisor = magnitude(b);
+
+    let negative = is_negative(a) != is_negative(b);
+    if divisor == 0 || dividend >= divisor {
+        return (word(negative, U43_MAX), word(negative, U43_MAX), true);
+    }
+
+    // two words' worth of quotient bits, most significant first
+    let mut remainder = dividend;
+    let mut quotient = 0_u128;
+    for _ in 0..86 {
+        remainder <<= 1;
+        quotient <<= 1;
+        if remainder >= divisor {
+            remainder -= divisor;
+            quotient |= 0b1;
+        }
+    }
+
+    (
+        word(negative, (quotient >> 43) as u64),
+        word(negative, quotient as u64 & U43_MAX),
+        false,
+    )
+}
+
+/// Implementation of the arithmetic orders a machine uses.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ArithmeticBackend {
+    /// [`Word`]'s own arithmetic, on the value of each word as a native
+    /// integer.
+    #[default]
+    Native,
+    /// Bit-serial arithmetic, see [`crate::serial`].
+    Serial,
+}
+
+impl ArithmeticBackend {
+    #[must_use]
+    pub fn overflowing_add(self, a: Word, b: Word) -> (Word, bool) {
+        match self {
+            Self::Native => a.overflowing_add(b),
+            Self::Serial => serial::overflowing_add(a, b),
+        }
+    }
+
+    #[must_use]
+    pub fn overflowing_sub(self, a: Word, b: Word) -> (Word, bool) {
+        match self {
+            Self::Native => a.overflowing_sub(b),
+            Self::Serial => serial::overflowing_sub(a, b),
+        }
+    }
+
+    #[must_use]
+    pub fn mul(self, a: Word, b: Word) -> (Word, Word) {
+        match self {
+            Self::Native => a.mul(b),
+            Self::Serial => serial::mul(a, b),
+        }
+    }
+
+    #[must_use]
+    pub fn overflowing_div(self, a: Word, b: Word) -> (Word, Word, bool) {
+        match self {
+            Self::Native => a.overflowing_div(b),
+            Self::Serial => serial::overflowing_div(a, b),
+        }
+    }
+}
+
+/// # Arithmetic checking
+impl Edvac {
+    /// Hands back `result` from an `order` unless arithmetic checking is on
+    /// and `reference` disagrees with it.
+    pub(crate) fn checked<T: PartialEq + Debug>(
+        &self,
+        order: OrderKind,
+        result: T,
+        reference: impl FnOnce() -> T,
+    ) -> Result<T, EdvacError> {
+        if !self.arithmetic_check {
+            return Ok(result);
+        }
+
+        let expected = reference();
+        if result == expected {
+            Ok(result)
+        } else {
+            error!(
+                "Arithmetic check failed on {:?}: got {:?}, reference got {:?}",
+                order, result, expected
+            );
+
+            Err(EdvacError::ArithmeticCheckFailure { order })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::EdvacStatus;
+
+    const EDGES: [i64; 9] = [
+        0,
+        1,
+        -1,
+        2,
+        -3,
+        4_294_967_296,
+        -65535,
+        U43_MAX as i64 - 1,
+        -(U43_MAX as i64),
+    ];
+
+    fn words() -> Vec<Word> {
+        let mut words: Vec<Word> = EDGES.iter().map(|&n| n.try_into().unwrap()).collect();
+        // -0
+        words.push(Word::from_bits(0b1));
+
+        words
+    }
+
+    #[test]
+    fn agrees_with_word() {
+        for a in words() {
+            for b in words() {
+                assert_eq!(a.overflowing_add(b), overflowing_add(a, b), "{a:?} + {b:?}");
+                assert_eq!(a.overflowing_sub(b), overflowing_sub(a, b), "{a:?} - {b:?}");
+                assert_eq!(a.mul(b), mul(a, b), "{a:?} * {b:?}");
+                assert_eq!(a.overflowing_div(b), overflowing_div(a, b), "{a:?} / {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn check_failure() {
+        let mut computer = Edvac {
+            arithmetic_check: true,
+            ..Default::default()
+        };
+        computer
+            .high_speed_memory
+            .load(vec![
+                (0o0000, inst!(A 0o0100 0o0101 0o0102 0o0001)),
+                (0o0001, inst!(H 0o0000 0o0000 0o0000 0o0000)),
+                (0o0100, 5.try_into().unwrap()),
+                (0o0101, (-7).try_into().unwrap()),
+            ])
+            .unwrap();
+
+        computer.initiate_pressed();
+        computer.continue_to_completion().unwrap();
+        assert!(matches!(computer.status, EdvacStatus::Halted { .. }));
+        assert_eq!(i64::from(computer.get(0o0102).unwrap()), -2);
+
+        // a unit that can't add at all
+        let result = computer.checked(OrderKind::Add, (Word::from_bits(0), false), || {
+            overflowing_add(5.try_into().unwrap(), (-7).try_into().unwrap())
+        });
+        assert_eq!(
+            result,
+            Err(EdvacError::ArithmeticCheckFailure {
+                order: OrderKind::Add
+            })
+        );
+
+        // ...which goes unnoticed without checking
+        computer.arithmetic_check = false;
+        assert!(computer
+            .checked(OrderKind::Add, (Word::from_bits(0), false), || {
+                unreachable!()
+            })
+            .is_ok());
+    }
+}
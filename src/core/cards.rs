@@ -0,0 +1,691 @@
+//! # Punched cards
+//! Later in its life the EDVAC did much of its input and output through IBM
+//! card equipment instead of paper tape and the typewriter. A [`CardUnit`] is a
+//! combined reader and punch that can be put in place of any wire spool
+//! (including Wire 0): wire orders naming that spool then read words off of the
+//! cards in its hopper, or punch words onto blank cards, instead of using the
+//! wire.
+//!
+//! ## Cards
+//! A [`Card`] has 80 columns of 12 rows each, from the top: 12, 11, 0 and 1 to
+//! 9. Characters are punched in the usual Hollerith code: a digit is a single
+//! punch in its row, letters add a 12, 11 or 0 "zone" punch to a digit, `+` is
+//! a 12 punch alone and `-` an 11 punch alone.
+//!
+//! ## Conversion
+//! Words are put onto cards in one of two ways, given by [`CardFormat`]:
+//! - [`CardFormat::Octal`] punches up to five words per card, in 16-column
+//!   fields, as they are written in a listing: a `+` or `-` followed by the 15
+//!   octal digits of the magnitude, without the spaces. Reading a card stops at
+//!   the first blank field, so cards can be punched by hand and need not be
+//!   full.
+//! - [`CardFormat::ColumnBinary`] punches 20 words per card, 4 columns to a
+//!   word, with the raw bits of each word in the bottom 44 rows of its 4
+//!   columns (row 12 of the first column is the most significant).
+//!
+//! None of the sources at hand describe the conversion used with the EDVAC's
+//! card equipment, so both formats are this emulator's own, and decks in them
+//! are not historical ones.
+//!
+//! Each order starts on a fresh card. A read order feeds cards from the hopper
+//! until it has all the words it needs, and a record order punches its words
+//! onto as many cards as it takes; whatever is left of the last card goes
+//! unused. Cards can't be moved backward, so backward wire orders halt, and
+//! the "translate" sub-order reads words without storing them. "Read fifth
+//! address" reads words in pairs, an address (the low 10 bits of the magnitude
+//! of the first word) and the word to be stored there.
+//!
+//! Reading or punching a card takes far longer than anything else the machine
+//! does, [`CARD_READ_TIME`] or [`CARD_PUNCH_TIME`], and the simulated clock is
+//! charged for every card fed. Those are typical speeds for IBM equipment of the
+//! time, not figures for the EDVAC's.
+//!
+//! ## Deck files
+//! Decks are kept either as text, one line per card, with each character
+//! punched in its column (lines shorter than 80 columns are blank to the right,
+//! and the characters that can be punched are listed in [`CHARACTERS`]), or as
+//! binary column images, 160 bytes per card: 2 bytes for each column, the first
+//! holding rows 12 to 3 and the second rows 4 to 9, in their low 6 bits.
+
+use std::{
+    fmt,
+    io::{self, Read, Write},
+};
+
+use crate::{
+    error::EdvacError,
+    high_speed_memory::ADDRESS_MASK,
+    observer::EdvacEvent,
+    wire::WireSpool,
+    word::{Word, BIT_WIDTH},
+    Edvac,
+};
+
+pub const COLUMNS: usize = 80;
+
+/// Time to read a card, for a reader feeding 150 cards a minute.
+pub const CARD_READ_TIME: u64 = 400_000;
+
+/// Time to punch a card, for a punch doing 100 cards a minute.
+pub const CARD_PUNCH_TIME: u64 = 600_000;
+
+/// Characters that can be punched from a text deck, and so that a card has to
+/// be made of to be written back out as text.
+pub const CHARACTERS: &str = " 0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ+-/.,*$";
+
+/// The bit for `row` (12, 11 or 0 to 9) in a column.
+const fn row(row: u32) -> u16 {
+    match row {
+        12 => 1 << 11,
+        11 => 1 << 10,
+        row => 1 << (9 - row),
+    }
+}
+
+/// The Hollerith code for `c`, if it has one.
+fn hollerith(c: char) -> Option<u16> {
+    let offset = |from: char| c as u32 - from as u32;
+
+    Some(match c {
+        ' ' => 0,
+        '0'..='9' => row(offset('0')),
+        'A'..='I' => row(12) | row(offset('A') + 1),
+        'J'..='R' => row(11) | row(offset('J') + 1),
+        'S'..='Z' => row(0) | row(offset('S') + 2),
+        '+' => row(12),
+        '-' => row(11),
+        '/' => row(0) | row(1),
+        '.' => row(12) | row(3) | row(8),
+        ',' => row(0) | row(3) | row(8),
+        '*' => row(11) | row(4) | row(8),
+        '$' => row(11) | row(3) | row(8),
+        _ => return None,
+    })
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CardFormat {
+    #[default]
+    Octal,
+    ColumnBinary,
+}
+
+impl CardFormat {
+    #[must_use]
+    pub fn words_per_card(self) -> usize {
+        match self {
+            CardFormat::Octal => 5,
+            CardFormat::ColumnBinary => 20,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CardError {
+    /// Column `column` (1-based) of the card on `line` (1-based) of a text deck
+    /// can't be punched.
+    BadCharacter { line: usize, column: usize },
+    /// The card on `line` (1-based) of a text deck is more than 80 columns.
+    TooLong { line: usize },
+    /// Column `column` (1-based) of card `card` (0-based) isn't a character,
+    /// so the deck can't be written as text.
+    NotText { card: usize, column: usize },
+}
+
+impl fmt::Display for CardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CardError::BadCharacter { line, column } => {
+                write!(f, "line {}, column {}: can't be punched", line, column)
+            }
+            CardError::TooLong { line } => write!(f, "line {}: more than 80 columns", line),
+            CardError::NotText { card, column } => {
+                write!(f, "card {}, column {}: not a character", card, column)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CardError {}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Card {
+    /// Row 12 is the most significant of the 12 bits, and row 9 the least.
+    columns: [u16; COLUMNS],
+}
+
+impl Default for Card {
+    fn default() -> Self {
+        Card {
+            columns: [0; COLUMNS],
+        }
+    }
+}
+
+impl Card {
+    /// Builds a card from its columns. Bits past the 12 rows are ignored.
+    #[must_use]
+    pub fn new(columns: [u16; COLUMNS]) -> Card {
+        Card {
+            columns: columns.map(|column| column & 0o7777),
+        }
+    }
+
+    #[must_use]
+    pub fn columns(&self) -> &[u16; COLUMNS] {
+        &self.columns
+    }
+
+    /// Punches `words` onto a card, as many as fit.
+    #[must_use]
+    pub fn punch_words(format: CardFormat, words: &[Word]) -> Card {
+        let mut card = Card::default();
+        let fields = card.columns.chunks_mut(COLUMNS / format.words_per_card());
+
+        for (field, word) in fields.zip(words) {
+            let bits = word.get_bits();
+
+            match format {
+                CardFormat::Octal => {
+                    field[0] = if bits & 0b1 == 0 { row(12) } else { row(11) };
+
+                    let magnitude = bits >> 1;
+                    for (digit, column) in field[1..].iter_mut().rev().enumerate() {
+                        *column = row(((magnitude >> (3 * digit)) & 0o7) as u32);
+                    }
+                }
+                CardFormat::ColumnBinary => {
+                    for (i, column) in field.iter_mut().enumerate() {
+                        *column = ((bits >> (12 * (3 - i))) & 0o7777) as u16;
+                    }
+                }
+            }
+        }
+
+        card
+    }
+
+    /// The words on the card, or `None` if it can't be read as `format`.
+    #[must_use]
+    pub fn to_words(&self, format: CardFormat) -> Option<Vec<Word>> {
+        let fields = self.columns.chunks(COLUMNS / format.words_per_card());
+
+        match format {
+            CardFormat::Octal => {
+                let mut words = Vec::new();
+
+                for field in fields.take_while(|field| field.iter().any(|&column| column != 0)) {
+                    let sign = match field[0] {
+                        column if column == row(12) => 0,
+                        column if column == row(11) => 1,
+                        _ => return None,
+                    };
+
+                    let mut magnitude: u64 = 0;
+                    for &column in &field[1..] {
+                        let digit = (0..=7).find(|&digit| column == row(digit))?;
+                        magnitude = (magnitude << 3) | u64::from(digit);
+                    }
+
+                    if magnitude >> (BIT_WIDTH - 1) != 0 {
+                        return None;
+                    }
+                    words.push(Word::from_bits((magnitude << 1) | sign));
+                }
+
+                Some(words)
+            }
+            CardFormat::ColumnBinary => Some(
+                fields
+                    .map(|field| {
+                        let bits = field
+                            .iter()
+                            .fold(0, |bits, &column| (bits << 12) | u64::from(column));
+
+                        Word::from_bits(bits & ((1 << BIT_WIDTH) - 1))
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct CardDeck {
+    cards: Vec<Card>,
+}
+
+impl CardDeck {
+    #[must_use]
+    pub fn new(cards: Vec<Card>) -> CardDeck {
+        CardDeck { cards }
+    }
+
+    #[must_use]
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// Punches `words` onto as many cards as it takes.
+    #[must_use]
+    pub fn punch_words(format: CardFormat, words: &[Word]) -> CardDeck {
+        CardDeck {
+            cards: words
+                .chunks(format.words_per_card())
+                .map(|words| Card::punch_words(format, words))
+                .collect(),
+        }
+    }
+
+    /// Reads a text deck (see the [module documentation](self)).
+    pub fn parse(text: &str) -> Result<CardDeck, CardError> {
+        let mut cards = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let mut columns = [0; COLUMNS];
+
+            for (column, c) in line.chars().enumerate() {
+                *columns
+                    .get_mut(column)
+                    .ok_or(CardError::TooLong { line: index + 1 })? =
+                    hollerith(c).ok_or(CardError::BadCharacter {
+                        line: index + 1,
+                        column: column + 1,
+                    })?;
+            }
+
+            cards.push(Card { columns });
+        }
+
+        Ok(CardDeck { cards })
+    }
+
+    /// Writes the deck out as text, without trailing blanks.
+    pub fn to_text(&self) -> Result<String, CardError> {
+        let mut text = String::new();
+
+        for (index, card) in self.cards.iter().enumerate() {
+            let mut line = String::new();
+
+            for (column, &code) in card.columns.iter().enumerate() {
+                line.push(
+                    CHARACTERS
+                        .chars()
+                        .find(|&c| hollerith(c) == Some(code))
+                        .ok_or(CardError::NotText {
+                            card: index,
+                            column: column + 1,
+                        })?,
+                );
+            }
+
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+
+        Ok(text)
+    }
+
+    /// Writes the deck out as binary column images.
+    pub fn save(&self, mut writer: impl Write) -> io::Result<()> {
+        for card in &self.cards {
+            for &column in &card.columns {
+                writer.write_all(&[(column >> 6) as u8, (column & 0o77) as u8])?;
+            }
+        }
+
+        writer.flush()
+    }
+
+    /// Reads a deck of binary column images written by [`CardDeck::save`].
+    pub fn load(mut reader: impl Read) -> io::Result<CardDeck> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        if bytes.len() % (2 * COLUMNS) != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "deck ends partway through a card",
+            ));
+        }
+
+        let cards = bytes
+            .chunks(2 * COLUMNS)
+            .map(|card| {
+                let mut columns = [0; COLUMNS];
+                for (column, bytes) in columns.iter_mut().zip(card.chunks(2)) {
+                    *column = (u16::from(bytes[0] & 0o77) << 6) | u16::from(bytes[1] & 0o77);
+                }
+
+                Card { columns }
+            })
+            .collect();
+
+        Ok(CardDeck { cards })
+    }
+}
+
+/// A card reader and punch standing in for a wire spool.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CardUnit {
+    /// The spool wire orders name to use the unit.
+    pub spool: WireSpool,
+    pub format: CardFormat,
+
+    hopper: CardDeck,
+    /// Index in the hopper of the next card to be read.
+    next_card: usize,
+    stacker: CardDeck,
+}
+
+impl CardUnit {
+    #[must_use]
+    pub fn new(spool: WireSpool, format: CardFormat) -> CardUnit {
+        CardUnit {
+            spool,
+            format,
+            hopper: CardDeck::default(),
+            next_card: 0,
+            stacker: CardDeck::default(),
+        }
+    }
+
+    /// Replaces whatever is left in the hopper with `deck`.
+    pub fn load_hopper(&mut self, deck: CardDeck) {
+        self.hopper = deck;
+        self.next_card = 0;
+    }
+
+    /// The cards that haven't been read yet.
+    #[must_use]
+    pub fn hopper(&self) -> &[Card] {
+        &self.hopper.cards[self.next_card..]
+    }
+
+    /// Number of cards read since the hopper was loaded.
+    #[must_use]
+    pub fn cards_read(&self) -> usize {
+        self.next_card
+    }
+
+    /// Every card punched since the stacker was last emptied.
+    #[must_use]
+    pub fn punched(&self) -> &CardDeck {
+        &self.stacker
+    }
+
+    /// Hands back every card punched so far, emptying the stacker.
+    pub fn take_punched(&mut self) -> CardDeck {
+        std::mem::take(&mut self.stacker)
+    }
+
+    /// How far the unit has got, for undoing steps.
+    pub(crate) fn position(&self) -> (usize, usize) {
+        (self.next_card, self.stacker.cards.len())
+    }
+
+    pub(crate) fn set_position(&mut self, (next_card, punched): (usize, usize)) {
+        self.next_card = next_card;
+        self.stacker.cards.truncate(punched);
+    }
+
+    /// A unit part way through `hopper`, for snapshots.
+    pub(crate) fn with_decks(
+        spool: WireSpool,
+        format: CardFormat,
+        hopper: CardDeck,
+        next_card: usize,
+        stacker: CardDeck,
+    ) -> CardUnit {
+        CardUnit {
+            spool,
+            format,
+            hopper,
+            next_card,
+            stacker,
+        }
+    }
+
+    /// The whole hopper, including cards already read, for snapshots.
+    pub(crate) fn hopper_deck(&self) -> &CardDeck {
+        &self.hopper
+    }
+}
+
+/// # Punched cards
+impl Edvac {
+    /// Whether the card unit is standing in for `wire_spool`.
+    pub(crate) fn card_unit_on(&self, wire_spool: WireSpool) -> bool {
+        matches!(&self.card_unit, Some(unit) if unit.spool == wire_spool)
+    }
+
+    /// Feeds the next card from the hopper, giving the words on it.
+    pub(crate) fn read_card(&mut self) -> Result<Vec<Word>, EdvacError> {
+        let unit = self
+            .card_unit
+            .as_mut()
+            .expect("card orders need a card unit");
+        let spool = unit.spool;
+
+        let card = *unit
+            .hopper
+            .cards
+            .get(unit.next_card)
+            .ok_or(EdvacError::CardHopperEmpty { spool })?;
+        let words = card
+            .to_words(unit.format)
+            .ok_or(EdvacError::UnreadableCard { spool })?;
+        unit.next_card += 1;
+
+        self.clock.advance(CARD_READ_TIME);
+        self.notify(EdvacEvent::CardRead { spool, card });
+
+        Ok(words)
+    }
+
+    pub(crate) fn punch_card(&mut self, words: &[Word]) {
+        let unit = self
+            .card_unit
+            .as_mut()
+            .expect("card orders need a card unit");
+        let spool = unit.spool;
+
+        let card = Card::punch_words(unit.format, words);
+        unit.stacker.cards.push(card);
+
+        self.clock.advance(CARD_PUNCH_TIME);
+        self.notify(EdvacEvent::CardPunched { spool, card });
+    }
+
+    /// A wire order naming the spool the card unit stands in for. `operation`
+    /// is decoded as for the wires.
+    pub(crate) fn execute_cards(
+        &mut self,
+        operation: usize,
+        addresses: [usize; 4],
+    ) -> Result<(), EdvacError> {
+        let start = addresses[0];
+        let end = addresses[2];
+
+        if operation == 0o1 {
+            // Record (Memory -> Cards)
+            let per_card = self.card_unit.as_ref().unwrap().format.words_per_card();
+            let mut words = Vec::with_capacity(per_card);
+
+            let mut mem_index = start;
+            loop {
+                words.push(self.get(mem_index)?);
+
+                if words.len() == per_card || mem_index == end {
+                    self.punch_card(&words);
+                    words.clear();
+                }
+                if mem_index == end {
+                    break;
+                }
+                mem_index = (mem_index + 1) & ADDRESS_MASK as usize;
+            }
+        } else {
+            let mut words = Vec::new().into_iter();
+            let mut next_word = |edvac: &mut Edvac| -> Result<Word, EdvacError> {
+                loop {
+                    match words.next() {
+                        Some(word) => return Ok(word),
+                        None => words = edvac.read_card()?.into_iter(),
+                    }
+                }
+            };
+
+            let mut mem_index = start;
+            loop {
+                match operation {
+                    // Translate, skip words
+                    0o0 => {
+                        next_word(self)?;
+                    }
+                    // Read (Cards -> Memory)
+                    0o2 => {
+                        let word = next_word(self)?;
+                        self.set(mem_index, word)?;
+                    }
+                    // Read 5th Addr
+                    0o3 => {
+                        mem_index = ((next_word(self)?.get_bits() >> 1) & ADDRESS_MASK) as usize;
+                        let word = next_word(self)?;
+                        self.set(mem_index, word)?;
+                    }
+                    _ => unreachable!(),
+                }
+
+                if mem_index == end {
+                    break;
+                }
+                mem_index = (mem_index + 1) & ADDRESS_MASK as usize;
+            }
+        }
+
+        self.state.initial_address_register = addresses[3];
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::EdvacStatus;
+
+    #[test]
+    fn conversion() {
+        let words: Vec<Word> = [1, -2, 0o1234567, 1 << 42, 0]
+            .iter()
+            .map(|&n| n.try_into().unwrap())
+            .chain([Word::from_bits(u64::MAX >> 20)])
+            .collect();
+
+        for format in [CardFormat::Octal, CardFormat::ColumnBinary] {
+            let deck = CardDeck::punch_words(format, &words);
+            let read: Vec<Word> = deck
+                .cards()
+                .iter()
+                .flat_map(|card| card.to_words(format).unwrap())
+                .take(words.len())
+                .collect();
+            assert_eq!(read, words);
+
+            let mut binary = Vec::new();
+            deck.save(&mut binary).unwrap();
+            assert_eq!(binary.len(), deck.cards().len() * 160);
+            assert_eq!(CardDeck::load(binary.as_slice()).unwrap(), deck);
+        }
+
+        // octal cards are text, and so can be punched by hand
+        let deck = CardDeck::punch_words(CardFormat::Octal, &words[..2]);
+        let text = deck.to_text().unwrap();
+        assert_eq!(text, "+000000000000001-000000000000002\n");
+        assert_eq!(CardDeck::parse(&text).unwrap(), deck);
+
+        assert_eq!(
+            CardDeck::parse("HELLO, WORLD\n+0%"),
+            Err(CardError::BadCharacter { line: 2, column: 3 })
+        );
+        assert_eq!(
+            CardDeck::parse(&"0".repeat(81)),
+            Err(CardError::TooLong { line: 1 })
+        );
+        assert_eq!(
+            CardDeck::parse("*0000000000000001")
+                .map(|deck| deck.cards()[0].to_words(CardFormat::Octal)),
+            Ok(None)
+        );
+        assert!(CardDeck::punch_words(CardFormat::ColumnBinary, &words)
+            .to_text()
+            .is_err());
+    }
+
+    #[test]
+    fn card_unit() {
+        let mut computer = Edvac::default();
+        computer.journal.set_capacity(10);
+
+        let mut unit = CardUnit::new(WireSpool::Two, CardFormat::Octal);
+        unit.load_hopper(
+            CardDeck::parse(concat!(
+                "+000000000000003+000000000000004\n",
+                "+000000000000100-000000000000005+000000000000000+000000000000006\n",
+            ))
+            .unwrap(),
+        );
+        computer.card_unit = Some(unit);
+
+        computer
+            .high_speed_memory
+            .load(vec![
+                // read two words, load pairs, punch everything read
+                (0o0000, inst!(W 0o0010 0o0202 0o0011 0o0001)),
+                (0o0001, inst!(W 0o0000 0o0302 0o0000 0o0002)),
+                (0o0002, inst!(W 0o0010 0o0102 0o0011 0o0003)),
+                (0o0003, inst!(H 0o0000 0o0000 0o0000 0o0000)),
+            ])
+            .unwrap();
+
+        computer.initiate_pressed();
+        computer.continue_to_completion().unwrap();
+
+        let memory = computer.high_speed_memory.dump();
+        assert_eq!(memory[0o0010], 3.try_into().unwrap());
+        assert_eq!(memory[0o0011], 4.try_into().unwrap());
+        assert_eq!(memory[0o0100], (-5).try_into().unwrap());
+        assert_eq!(memory[0o0000], 6.try_into().unwrap());
+        assert!(computer.clock.pulse_times() > 2 * CARD_READ_TIME + CARD_PUNCH_TIME);
+
+        let unit = computer.card_unit.as_ref().unwrap();
+        assert!(unit.hopper().is_empty());
+        assert_eq!(
+            unit.punched().to_text().unwrap(),
+            "+000000000000003+000000000000004\n"
+        );
+        // none of the wires were touched
+        assert_eq!(computer.low_speed_memory[1].extent(), 0);
+
+        while computer.step_back() {}
+        let unit = computer.card_unit.as_ref().unwrap();
+        assert_eq!(unit.hopper().len(), 2);
+        assert!(unit.punched().cards().is_empty());
+
+        // reading past the last card
+        computer
+            .high_speed_memory
+            .load(vec![(0o0000, inst!(W 0o0010 0o0202 0o0020 0o0001))])
+            .unwrap();
+        computer.initiate_pressed();
+        assert_eq!(
+            computer.continue_to_completion(),
+            Err(EdvacError::CardHopperEmpty {
+                spool: WireSpool::Two
+            })
+        );
+        assert!(matches!(computer.status, EdvacStatus::Faulted { .. }));
+    }
+}
@@ -1,13 +1,26 @@
-use crate::orders::Order;
-use crate::wire::{WireShift, WireSpool};
-use crate::word::Word;
-
-use log::trace;
-
-#[derive(Clone, Copy, PartialEq, Eq)]
+use crate::debugger::{Access, Debugger};
+use crate::error::EdvacError;
+use crate::high_speed_memory::HighSpeedMemory;
+use crate::high_speed_memory::ADDRESS_WIDTH;
+use crate::observer::{EdvacEvent, EdvacObserver};
+use crate::orders::OrderSource;
+use crate::transport::{WireActivity, WireEndAction, WireTransport};
+use crate::wire::{WireEnd, WireShift, WireSpool};
+use crate::word::{Word, BIT_WIDTH};
+
+use log::{debug, error, trace, warn};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum EdvacStatus {
     Running,
-    Halted { resume_addr: usize },
+    Halted {
+        resume_addr: usize,
+    },
+    /// Halted because something went wrong; see [`EdvacError`].
+    Faulted {
+        resume_addr: usize,
+        error: EdvacError,
+    },
 }
 
 impl Default for EdvacStatus {
@@ -26,37 +39,163 @@ pub struct Edvac {
     pub status: EdvacStatus,
 
     pub low_speed_memory: [crate::wire::Wire; 3],
+
+    pub clock: crate::timing::SimulatedClock,
+    /// The handlers for Wires 1 to 3; see [`crate::transport`].
+    pub wire_transports: [WireTransport; 3],
+    /// Whatever is recorded onto Wire 0 is typed here instead.
+    pub typewriter: crate::typewriter::Typewriter,
+    /// Auxiliary memory added to the machine later in its life; `None` leaves
+    /// the machine as it was built. See [`crate::drum`].
+    pub drum: Option<crate::drum::Drum>,
+    /// Card reader and punch standing in for one of the wire spools, if any.
+    /// See [`crate::cards`].
+    pub card_unit: Option<crate::cards::CardUnit>,
+    /// How the arithmetic orders are worked out; see [`crate::arithmetic`].
+    pub arithmetic_backend: crate::arithmetic::ArithmeticBackend,
+    /// Whether every arithmetic order is checked against a second
+    /// implementation; see [`crate::arithmetic`].
+    pub arithmetic_check: bool,
+
+    pub debugger: Debugger,
+    pub journal: crate::journal::Journal,
+
+    pub(crate) observers: Vec<Box<dyn EdvacObserver + Send>>,
+    /// Set while an order is being executed, so that the machine halting during
+    /// it is only reported once it is done.
+    pub(crate) executing: bool,
 }
 
 /// # General
 impl Edvac {
     pub(crate) fn halt(&mut self, resume_addr: usize) {
         self.status = EdvacStatus::Halted { resume_addr };
+
+        if !self.executing {
+            self.notify(EdvacEvent::Halted { resume_addr });
+        }
+    }
+
+    pub(crate) fn fault(&mut self, error: EdvacError, resume_addr: usize) {
+        error!("Machine fault at {:0>4o}: {}", resume_addr, error);
+
+        self.status = EdvacStatus::Faulted { resume_addr, error };
+
+        self.notify(EdvacEvent::Faulted { resume_addr, error });
+    }
+
+    /// Runs `operation`, recording any fault it raises so that the machine can
+    /// be resumed from the order that caused it.
+    ///
+    /// Everything that steps the machine goes through here, so this is also
+    /// where steps are journaled (see [`crate::journal`]).
+    pub(crate) fn guarded<T>(
+        &mut self,
+        operation: impl FnOnce(&mut Edvac) -> Result<T, EdvacError>,
+    ) -> Result<T, EdvacError> {
+        let resume_addr = self.state.initial_address_register;
+        let journaled = self.begin_step();
+        let result = operation(self);
+
+        if let Err(error) = result {
+            self.fault(error, resume_addr);
+        }
+
+        if journaled {
+            self.journal.commit();
+        }
+
+        result
     }
 }
 
 /// # High-speed memory operations
 impl Edvac {
-    pub(crate) fn get(&self, addr: usize) -> Word {
-        let value = self.high_speed_memory.get(addr, self.state.memory_mode);
+    pub(crate) fn get(&mut self, addr: usize) -> Result<Word, EdvacError> {
+        self.wait_for(addr)?;
+
+        let value = self.high_speed_memory.get(addr, self.state.memory_mode)?;
+        self.debugger
+            .memory_accessed(addr, self.state.memory_mode, Access::Read);
 
         trace!("Get {:0>4o}: {:0>44b}", addr, value.get_bits());
+        self.notify(EdvacEvent::MemoryRead {
+            address: addr,
+            word: value,
+        });
 
-        value
+        Ok(value)
     }
 
-    pub(crate) fn set(&mut self, addr: usize, val: Word) {
+    pub(crate) fn set(&mut self, addr: usize, val: Word) -> Result<(), EdvacError> {
+        self.wait_for(addr)?;
+
+        let physical_address = HighSpeedMemory::physical_address(addr, self.state.memory_mode)?;
+        let prior = self.high_speed_memory.get(addr, self.state.memory_mode)?;
+        self.journal.memory_changed(physical_address, prior);
+
         trace!("Set {:0>4o} to {:0>44b}", addr, val.get_bits(),);
 
         self.high_speed_memory
-            .set(addr, self.state.memory_mode, val);
+            .set(addr, self.state.memory_mode, val)?;
+        self.debugger
+            .memory_accessed(addr, self.state.memory_mode, Access::Write);
+        self.notify(EdvacEvent::MemoryWrite {
+            address: addr,
+            word: val,
+        });
+
+        Ok(())
+    }
+
+    /// Charges the simulated clock for waiting on the word at `addr` to come
+    /// around its tank and then be transferred.
+    fn wait_for(&mut self, addr: usize) -> Result<(), EdvacError> {
+        let physical_address = HighSpeedMemory::physical_address(addr, self.state.memory_mode)?;
+        let wait = self.clock.access(physical_address);
+
+        trace!("Waited {} pulse times for {:0>4o}", wait, addr);
+
+        Ok(())
+    }
+
+    /// Simulated time spent running programs since the machine was created, in
+    /// microseconds. See [`crate::timing`] for what is and isn't accounted for.
+    #[must_use]
+    pub fn elapsed_micros(&self) -> u64 {
+        self.clock.micros()
     }
 }
 
 /// # Low-speed Memory operations
 impl Edvac {
-    pub(crate) fn read_word_from_wire(&mut self, wire_spool: WireSpool) -> Word {
-        if let Ok(index) = usize::try_from(wire_spool) {
+    /// Raises the condition for running into `end` of a wire, unless the
+    /// handler for the wire is set to just stop there.
+    fn wire_end(&self, wire_spool: WireSpool, end: WireEnd) -> Result<(), EdvacError> {
+        let index = usize::try_from(wire_spool).unwrap();
+
+        match self.wire_transports[index].end_action {
+            WireEndAction::Halt => Err(match end {
+                WireEnd::Beginning => EdvacError::BeginningOfWire { spool: wire_spool },
+                WireEnd::End => EdvacError::EndOfWire { spool: wire_spool },
+            }),
+            WireEndAction::Stop => {
+                debug!("Wire {} ran into its {:?}", index + 1, end);
+
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn read_word_from_wire(
+        &mut self,
+        wire_spool: WireSpool,
+    ) -> Result<Word, EdvacError> {
+        let value = if let Ok(index) = usize::try_from(wire_spool) {
+            if self.low_speed_memory[index].remaining() < BIT_WIDTH {
+                self.wire_end(wire_spool, WireEnd::End)?;
+            }
+
             let value = self.low_speed_memory[index].read_word();
 
             trace!(
@@ -72,22 +211,44 @@ impl Edvac {
             trace!("Read Aux. Input: {:0>44b}", value);
 
             Word::from_bits(value)
-        }
+        };
+
+        self.notify(EdvacEvent::WireRead {
+            spool: wire_spool,
+            word: value,
+        });
+
+        Ok(value)
     }
 
-    pub(crate) fn read_address_from_wire(&mut self, wire_spool: WireSpool) -> usize {
+    pub(crate) fn read_address_from_wire(
+        &mut self,
+        wire_spool: WireSpool,
+    ) -> Result<usize, EdvacError> {
         if let Ok(index) = usize::try_from(wire_spool) {
+            if self.low_speed_memory[index].remaining() < ADDRESS_WIDTH {
+                self.wire_end(wire_spool, WireEnd::End)?;
+            }
+
             let address = self.low_speed_memory[index].read_address();
 
             trace!("Read Addr. off Wire {}: {:0>4o}", index + 1, address);
+            self.notify(EdvacEvent::WireAddressRead {
+                spool: wire_spool,
+                address,
+            });
 
-            address
+            Ok(address)
         } else {
-            unimplemented!()
+            Err(EdvacError::ReadAddressFromWire { spool: wire_spool })
         }
     }
 
-    pub(crate) fn write_word_to_wire(&mut self, wire_spool: WireSpool, word: Word) {
+    pub(crate) fn write_word_to_wire(
+        &mut self,
+        wire_spool: WireSpool,
+        word: Word,
+    ) -> Result<(), EdvacError> {
         if let Ok(index) = usize::try_from(wire_spool) {
             trace!(
                 "Write Word to Wire {}: {:0>44b}",
@@ -95,52 +256,172 @@ impl Edvac {
                 word.get_bits()
             );
 
-            self.low_speed_memory[index].write_word(word)
+            let wire = &self.low_speed_memory[index];
+            if wire.remaining() < BIT_WIDTH {
+                self.wire_end(wire_spool, WireEnd::End)?;
+            }
+
+            let wire = &self.low_speed_memory[index];
+            self.journal.wire_changed(
+                index,
+                wire.index(),
+                wire.bits_at(wire.index(), BIT_WIDTH),
+                BIT_WIDTH,
+            );
+
+            self.low_speed_memory[index].write_word(word);
+            self.notify(EdvacEvent::WireWrite {
+                spool: wire_spool,
+                word,
+            });
+
+            Ok(())
         } else {
-            unimplemented!()
+            trace!("Type Word: {:0>44b}", word.get_bits());
+
+            self.typewriter.type_word(word);
+            self.notify(EdvacEvent::WireWrite {
+                spool: wire_spool,
+                word,
+            });
+
+            Ok(())
         }
     }
 
-    pub(crate) fn translate_wire(&mut self, wire_spool: WireSpool, shift: WireShift) {
+    /// Moves a wire, charging the simulated clock for the time its handler
+    /// takes to do so while doing `activity`.
+    pub(crate) fn translate_wire(
+        &mut self,
+        wire_spool: WireSpool,
+        shift: WireShift,
+        activity: WireActivity,
+    ) -> Result<(), EdvacError> {
         if let Ok(index) = usize::try_from(wire_spool) {
-            self.low_speed_memory[index].translate(shift);
+            let wire = &mut self.low_speed_memory[index];
+            let before = wire.index();
+            let result = wire.translate(shift);
+
+            // the wire stops at either end, so it may not have gone the whole way
+            let moved = match shift {
+                WireShift::Forward(_) => WireShift::Forward(wire.index() - before),
+                WireShift::Backward(_) => WireShift::Backward(before - wire.index()),
+            };
+            let time = self.wire_transports[index].moved(moved, activity);
+            self.clock.advance(time);
+
+            trace!("Wire {} took {} pulse times to move", index + 1, time);
+            self.notify(EdvacEvent::WireTranslated {
+                spool: wire_spool,
+                shift,
+            });
+
+            if let Err(end) = result {
+                self.wire_end(wire_spool, end)?;
+            }
         }
         // else condition is omitted as Wire 0 is treated as if it has "infinite
         // length"
+
+        Ok(())
     }
 }
 
 /// # Operating Modes
-/// Because I only emulate down to the level of individual orders, certain modes
-/// like "one cycle" or "one execute" are not supported. *Technically* these can
-/// be faked and emulated specifically, but that's not planned, at least for now
+/// Orders are emulated down to the level of their cycle and execute phases (see
+/// [`Edvac::cycle`]), which is enough for the "one cycle" and "one execute"
+/// modes of operation. Nothing finer than that is emulated.
+///
+/// If a fault comes up, the machine is put into [`EdvacStatus::Faulted`] and the
+/// fault is also returned.
 impl Edvac {
     /// Decodes and executes the next order, appropriately updating the state of
-    /// the machine.
-    pub fn step_once(&mut self) {
+    /// the machine. If an order has already been cycled into the order register,
+    /// only its execute phase remains and so that is all that is done.
+    pub fn step_once(&mut self) -> Result<(), EdvacError> {
         trace!("======= NEXT ORDER =======");
-        let order: Order = self.get(self.state.initial_address_register).into();
+        self.guarded(|edvac| {
+            if edvac.state.order_register.is_none() {
+                edvac.cycle(OrderSource::Memory)?;
+            }
+
+            edvac.execute_cycled()?;
 
-        self.execute_once(&order);
+            Ok(())
+        })
     }
 
     /// Executes one instruction from the Special Order switches on the front of
-    /// the machine.
-    pub fn execute_special_order(&mut self) {
+    /// the machine. If an order is already waiting in the order register, the
+    /// machine halts instead, leaving it there.
+    pub fn execute_special_order(&mut self) -> Result<(), EdvacError> {
         trace!("======= EXECUTING SPECIAL ORDER =======");
-        // save current execution address
-        let old_address = self.state.initial_address_register;
+        self.guarded(|edvac| {
+            if edvac.order_waiting() {
+                return Ok(());
+            }
 
-        self.execute_once(&self.state.special_order_switches.into());
+            edvac.cycle(OrderSource::SpecialOrderSwitches)?;
+            edvac.execute_cycled()?;
 
-        // the current fourth-address value of the special order instruction was
-        // saved to the IAR- the EDVAC does not do this, so we restore the saved
-        // execution address from before we ran the order and set the machine to
-        // Halt, per FuncDesc pg. "3-9"
-        let next_address = self.state.initial_address_register;
-        self.state.initial_address_register = old_address;
+            Ok(())
+        })
+    }
 
-        self.halt(next_address)
+    /// Runs only the cycle phase of the next order, then halts. `special`
+    /// selects between the Special and Normal versions of the mode. If an order
+    /// is already waiting in the order register, the machine just halts.
+    pub fn one_cycle(&mut self, special: bool) -> Result<(), EdvacError> {
+        self.guarded(|edvac| {
+            if edvac.order_waiting() {
+                return Ok(());
+            }
+
+            edvac.cycle(if special {
+                OrderSource::SpecialOrderSwitches
+            } else {
+                OrderSource::Memory
+            })?;
+
+            edvac.halt_pressed();
+
+            Ok(())
+        })
+    }
+
+    /// Runs only the execute phase of the order in the order register, then
+    /// halts. If the register is empty, the next order is cycled in first.
+    pub fn one_execute(&mut self, special: bool) -> Result<(), EdvacError> {
+        self.guarded(|edvac| {
+            if edvac.state.order_register.is_none() {
+                edvac.cycle(if special {
+                    OrderSource::SpecialOrderSwitches
+                } else {
+                    OrderSource::Memory
+                })?;
+            }
+
+            edvac.execute_cycled()?;
+
+            if edvac.status == EdvacStatus::Running {
+                edvac.halt_pressed();
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Halts the machine if an order has been cycled into the order register but
+    /// not yet executed, so that cycling another in doesn't lose it.
+    fn order_waiting(&mut self) -> bool {
+        if let Some(cycled) = self.state.order_register {
+            warn!("{:?} is still waiting in the order register", cycled.order);
+            self.halt_pressed();
+
+            true
+        } else {
+            false
+        }
     }
 
     /// Executes until the Initial Address Register equals Address A (breakpoint
@@ -149,12 +430,14 @@ impl Edvac {
     /// Note that this method is provided mostly for completeness; when packaged
     /// as a binary, the UI handles this operating mode. This way, we don't need
     /// to bother with async stuff.
-    pub fn continue_to_address_a(&mut self) {
+    pub fn continue_to_address_a(&mut self) -> Result<(), EdvacError> {
         while self.status == EdvacStatus::Running
             && self.state.initial_address_register != self.state.address_a_switches
         {
-            self.step_once();
+            self.step_once()?;
         }
+
+        Ok(())
     }
 
     /// Executes until the machine Halts.
@@ -162,10 +445,12 @@ impl Edvac {
     /// Note that this method is provided mostly for completeness; when packaged
     /// as a binary, the UI handles this operating mode. This way, we don't need
     /// to bother with async stuff.
-    pub fn continue_to_completion(&mut self) {
+    pub fn continue_to_completion(&mut self) -> Result<(), EdvacError> {
         while self.status == EdvacStatus::Running {
-            self.step_once();
+            self.step_once()?;
         }
+
+        Ok(())
     }
 }
 
@@ -176,10 +461,15 @@ impl Edvac {
         self.state = crate::operating_console::State::default();
         self.high_speed_memory = crate::high_speed_memory::HighSpeedMemory::default();
         self.status = EdvacStatus::default();
+        self.journal.clear();
     }
 
     pub fn initiate_pressed(&mut self) {
-        if let EdvacStatus::Halted { resume_addr } = self.status {
+        self.debugger.clear_stops();
+
+        if let EdvacStatus::Halted { resume_addr } | EdvacStatus::Faulted { resume_addr, .. } =
+            self.status
+        {
             self.state.initial_address_register = resume_addr;
 
             self.status = EdvacStatus::Running;
@@ -187,8 +477,188 @@ impl Edvac {
     }
 
     pub fn halt_pressed(&mut self) {
-        self.status = EdvacStatus::Halted {
-            resume_addr: self.state.initial_address_register,
+        self.halt(self.state.initial_address_register);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fault_halts_machine() {
+        let mut computer = Edvac::default();
+
+        computer.initiate_pressed();
+        computer.state.initial_address_register = 0o2000;
+
+        let error = EdvacError::AddressOutOfRange { address: 0o2000 };
+
+        assert_eq!(computer.step_once(), Err(error));
+        assert_eq!(
+            computer.status,
+            EdvacStatus::Faulted {
+                resume_addr: 0o2000,
+                error
+            }
+        );
+    }
+
+    #[test]
+    fn one_cycle_one_execute() {
+        use crate::orders::{CycledOrder, Order};
+
+        let mut computer = Edvac::default();
+        computer
+            .high_speed_memory
+            .load(vec![
+                (0o0000, inst!(A 0o0100 0o0101 0o0102 0o0001)),
+                (0o0001, inst!(H 0o0000 0o0000 0o0000 0o0002)),
+                (0o0100, 2.try_into().unwrap()),
+                (0o0101, 3.try_into().unwrap()),
+            ])
+            .unwrap();
+        computer.state.special_order_switches = inst!(H 0o0000 0o0000 0o0000 0o0000);
+
+        computer.initiate_pressed();
+        computer.one_cycle(false).unwrap();
+        assert_eq!(
+            computer.state.order_register,
+            Some(CycledOrder {
+                order: Order::from(inst!(A 0o0100 0o0101 0o0102 0o0001)),
+                source: OrderSource::Memory,
+            })
+        );
+        assert_eq!(
+            computer.status,
+            EdvacStatus::Halted {
+                resume_addr: 0o0000
+            }
+        );
+        assert_eq!(i64::from(computer.get(0o0102).unwrap()), 0);
+
+        // the cycled order isn't lost to another one
+        computer.initiate_pressed();
+        computer.execute_special_order().unwrap();
+        computer.one_cycle(true).unwrap();
+        assert_eq!(
+            computer.state.order_register.unwrap().source,
+            OrderSource::Memory
+        );
+
+        computer.initiate_pressed();
+        computer.one_execute(false).unwrap();
+        assert_eq!(computer.state.order_register, None);
+        assert_eq!(
+            computer.status,
+            EdvacStatus::Halted {
+                resume_addr: 0o0001
+            }
+        );
+        assert_eq!(i64::from(computer.get(0o0102).unwrap()), 5);
+
+        // with nothing cycled in, the next order is cycled and executed
+        computer.initiate_pressed();
+        computer.one_execute(false).unwrap();
+        assert_eq!(
+            computer.status,
+            EdvacStatus::Halted {
+                resume_addr: 0o0002
+            }
+        );
+    }
+
+    #[test]
+    fn wire_ends() {
+        use crate::wire::WIRE_SIZE;
+
+        let mut computer = Edvac::default();
+        computer
+            .high_speed_memory
+            .load(vec![
+                // read 4 words off of Wire 1, forward
+                (0o0000, inst!(W 0o0010 0o0201 0o0013 0o0001)),
+                (0o0001, inst!(H 0o0000 0o0000 0o0000 0o0000)),
+                // read 2 words off of Wire 1, backward
+                (0o0002, inst!(W 0o0010 0o1201 0o0011 0o0001)),
+            ])
+            .unwrap();
+        let error = EdvacError::EndOfWire {
+            spool: WireSpool::One,
         };
+
+        // there are only 2 words left on the wire
+        computer.low_speed_memory[0].seek(WIRE_SIZE - 2 * BIT_WIDTH);
+        computer.initiate_pressed();
+        assert_eq!(computer.continue_to_completion(), Err(error));
+        assert_eq!(
+            computer.status,
+            EdvacStatus::Faulted {
+                resume_addr: 0o0000,
+                error
+            }
+        );
+
+        computer.wire_transports[0].end_action = WireEndAction::Stop;
+        computer.low_speed_memory[0].seek(WIRE_SIZE - 2 * BIT_WIDTH);
+        computer.initiate_pressed();
+        computer.continue_to_completion().unwrap();
+        assert_eq!(
+            computer.status,
+            EdvacStatus::Halted {
+                resume_addr: 0o0000
+            }
+        );
+
+        // and 1 word before the head
+        computer.wire_transports[0].end_action = WireEndAction::Halt;
+        computer.low_speed_memory[0].seek(BIT_WIDTH);
+        computer.status = EdvacStatus::Halted {
+            resume_addr: 0o0002,
+        };
+        computer.initiate_pressed();
+        assert_eq!(
+            computer.continue_to_completion(),
+            Err(EdvacError::BeginningOfWire {
+                spool: WireSpool::One
+            })
+        );
+    }
+
+    #[test]
+    fn negative_zero_orders() {
+        let mut computer = Edvac::default();
+        computer
+            .high_speed_memory
+            .load(vec![
+                // -0 and +0 compare as equal, and -0 is less than 1
+                (0o0000, inst!(C 0o0100 0o0101 0o0010 0o0001)),
+                (0o0001, inst!(C 0o0101 0o0100 0o0010 0o0002)),
+                (0o0002, inst!(C 0o0100 0o0102 0o0003 0o0010)),
+                // move +5 and -1 one place right, whole words and all
+                (0o0003, inst!(E 0o0102 0o1017 0o0103 0o0004)),
+                (0o0004, inst!(E 0o0104 0o1017 0o0105 0o0005)),
+                (0o0005, inst!(H 0o0000 0o0000 0o0000 0o0077)),
+                (0o0010, inst!(H 0o0000 0o0000 0o0000 0o0066)),
+                (0o0100, Word::from_bits(0b1)),
+                (0o0101, Word::from_bits(0b0)),
+                (0o0102, 5.try_into().unwrap()),
+                (0o0104, (-1).try_into().unwrap()),
+            ])
+            .unwrap();
+
+        computer.initiate_pressed();
+        computer.continue_to_completion().unwrap();
+        assert_eq!(
+            computer.status,
+            EdvacStatus::Halted {
+                resume_addr: 0o0077
+            }
+        );
+
+        // the sign is the word's own, not a bit shifted into its place, but a
+        // whole word shifted down to zero is +0
+        assert_eq!(i64::from(computer.get(0o0103).unwrap()), 2);
+        assert_eq!(computer.get(0o0105).unwrap().get_bits(), 0);
     }
 }
@@ -0,0 +1,322 @@
+//! # Debugger
+//! The Address A switches only allow for a single breakpoint, which is not much
+//! to go on when chasing a bug through a long routine. The [`Debugger`] adds any
+//! number of execution breakpoints (optionally conditional on the value of a
+//! word), watchpoints on reads and writes of high-speed memory, and breaking on
+//! excess capacity (overflow).
+//!
+//! Nothing here existed on the real machine. Breakpoints are checked as an
+//! order is about to be fetched from memory, and halt the machine before it is
+//! cycled in; resuming from there carries on past the breakpoint. Orders are
+//! never interrupted part of the way through: whatever else triggers during an
+//! order is collected, and the machine is halted once the order is done, as
+//! though Halt had been pressed. [`Debugger::stops`] then says why.
+
+use crate::{high_speed_memory::HighSpeedMemory, operating_console::MemoryMode, word::Word};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    Greater,
+}
+
+/// Holds when the word at `address` compares to `value` as given. Words are
+/// compared by their numeric value, so `+0` and `-0` are equal.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Condition {
+    pub address: usize,
+    pub comparison: Comparison,
+    pub value: Word,
+}
+
+impl Condition {
+    fn holds(&self, memory: &HighSpeedMemory, mode: MemoryMode) -> bool {
+        let word = match memory.get(self.address, mode) {
+            Ok(word) => i64::from(word),
+            Err(_) => return false,
+        };
+        let value = i64::from(self.value);
+
+        match self.comparison {
+            Comparison::Equal => word == value,
+            Comparison::NotEqual => word != value,
+            Comparison::Less => word < value,
+            Comparison::Greater => word > value,
+        }
+    }
+}
+
+/// Halts the machine before the order at `address` is fetched, like the
+/// Address A switches do in the "To Address A" mode of operation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Breakpoint {
+    pub address: usize,
+    /// If present, the breakpoint is only taken while this holds.
+    pub condition: Option<Condition>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Access {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+/// Halts the machine after an order touches the word at `address`. Fetching an
+/// order from memory counts as reading it.
+///
+/// Watchpoints follow the memory mode: under `L0`, watching 0o0001 also
+/// catches 0o1001, since both are the same word.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Watchpoint {
+    pub address: usize,
+    pub access: Access,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StopReason {
+    Breakpoint {
+        address: usize,
+    },
+    /// `access` is either [`Access::Read`] or [`Access::Write`].
+    Watchpoint {
+        address: usize,
+        access: Access,
+    },
+    /// The order at `address` exceeded the capacity of the machine.
+    Overflow {
+        address: usize,
+    },
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct Debugger {
+    pub breakpoints: Vec<Breakpoint>,
+    pub watchpoints: Vec<Watchpoint>,
+    pub break_on_overflow: bool,
+
+    stops: Vec<StopReason>,
+    /// The breakpoint the machine last halted at, which is passed over by the
+    /// next fetch so that the machine can be resumed from it.
+    taken: Option<usize>,
+}
+
+impl Debugger {
+    /// Why the machine was last stopped by the debugger, in the order things
+    /// were triggered. This is cleared when Initiate is pressed.
+    #[must_use]
+    pub fn stops(&self) -> &[StopReason] {
+        &self.stops
+    }
+
+    pub(crate) fn clear_stops(&mut self) {
+        self.stops.clear();
+    }
+
+    pub(crate) fn memory_accessed(&mut self, addr: usize, mode: MemoryMode, access: Access) {
+        let physical_address = HighSpeedMemory::physical_address(addr, mode).ok();
+
+        let triggered = self.watchpoints.iter().any(|watchpoint| {
+            (watchpoint.access == access || watchpoint.access == Access::ReadWrite)
+                && HighSpeedMemory::physical_address(watchpoint.address, mode).ok()
+                    == physical_address
+        });
+
+        if triggered {
+            self.stops.push(StopReason::Watchpoint {
+                address: addr,
+                access,
+            });
+        }
+    }
+
+    pub(crate) fn overflowed(&mut self, address: usize) {
+        if self.break_on_overflow {
+            self.stops.push(StopReason::Overflow { address });
+        }
+    }
+
+    /// Checks for a breakpoint on the order about to be fetched from
+    /// `addr`, returning whether the machine should halt before fetching it.
+    pub(crate) fn breakpoint_hit(
+        &mut self,
+        addr: usize,
+        memory: &HighSpeedMemory,
+        mode: MemoryMode,
+    ) -> bool {
+        if self.taken.take() == Some(addr) {
+            return false;
+        }
+
+        let triggered = self.breakpoints.iter().any(|breakpoint| {
+            breakpoint.address == addr
+                && breakpoint
+                    .condition
+                    .is_none_or(|condition| condition.holds(memory, mode))
+        });
+
+        if triggered {
+            self.stops.push(StopReason::Breakpoint { address: addr });
+            self.taken = Some(addr);
+        }
+
+        triggered
+    }
+
+    /// Whether anything triggered during the order just executed.
+    pub(crate) fn should_stop(&self) -> bool {
+        !self.stops.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        operating_console::ExcessCapacityAction, test_programs::dec_to_bin, word::U43_MAX, Edvac,
+        EdvacStatus,
+    };
+
+    fn computer() -> Edvac {
+        let mut computer = Edvac::default();
+        dec_to_bin::load(&mut computer);
+        computer.initiate_pressed();
+
+        computer
+    }
+
+    #[test]
+    fn breakpoints() {
+        let mut computer = computer();
+        computer.debugger.breakpoints.extend([
+            Breakpoint {
+                address: 0o1004,
+                condition: Some(Condition {
+                    address: 0o1006,
+                    comparison: Comparison::Greater,
+                    value: 0.try_into().unwrap(),
+                }),
+            },
+            Breakpoint {
+                address: 0o1037,
+                condition: Some(Condition {
+                    address: 0o1006,
+                    comparison: Comparison::Less,
+                    value: (-10_000_000_000).try_into().unwrap(),
+                }),
+            },
+        ]);
+
+        computer.continue_to_completion().unwrap();
+        assert_eq!(
+            computer.status,
+            EdvacStatus::Halted {
+                resume_addr: 0o1037
+            }
+        );
+        assert_eq!(
+            computer.debugger.stops(),
+            [StopReason::Breakpoint { address: 0o1037 }]
+        );
+
+        computer.debugger.breakpoints.clear();
+        computer.initiate_pressed();
+        assert!(computer.debugger.stops().is_empty());
+
+        computer.continue_to_completion().unwrap();
+        assert_eq!(
+            computer.status,
+            EdvacStatus::Halted {
+                resume_addr: 0o0000
+            }
+        );
+    }
+
+    #[test]
+    fn breakpoint_on_first_order() {
+        let mut computer = computer();
+        computer.debugger.breakpoints.push(Breakpoint {
+            address: 0o0000,
+            condition: None,
+        });
+
+        computer.continue_to_completion().unwrap();
+        assert_eq!(
+            computer.status,
+            EdvacStatus::Halted {
+                resume_addr: 0o0000
+            }
+        );
+        assert_eq!(
+            computer.debugger.stops(),
+            [StopReason::Breakpoint { address: 0o0000 }]
+        );
+        assert_eq!(computer.state.order_register, None);
+
+        // resuming carries on past it, to the program's own Halt
+        computer.initiate_pressed();
+        computer.continue_to_completion().unwrap();
+        assert_eq!(
+            computer.status,
+            EdvacStatus::Halted {
+                resume_addr: 0o0000
+            }
+        );
+        assert!(computer.debugger.stops().is_empty());
+    }
+
+    #[test]
+    fn watchpoints() {
+        let mut computer = computer();
+        computer.debugger.watchpoints.push(Watchpoint {
+            address: 0o1047,
+            access: Access::Write,
+        });
+
+        computer.continue_to_completion().unwrap();
+        assert_eq!(
+            computer.status,
+            EdvacStatus::Halted {
+                resume_addr: 0o1004
+            }
+        );
+        assert_eq!(
+            computer.debugger.stops(),
+            [StopReason::Watchpoint {
+                address: 0o1047,
+                access: Access::Write
+            }]
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let mut computer = Edvac::default();
+        computer
+            .high_speed_memory
+            .load(vec![
+                (0o0000, inst!(A 0o0010 0o0010 0o0011 0o0001)),
+                (0o0001, inst!(H 0o0000 0o0000 0o0000 0o0000)),
+                (0o0010, (U43_MAX as i64).try_into().unwrap()),
+            ])
+            .unwrap();
+        computer.state.excess_capacity_action_add = ExcessCapacityAction::Ignore;
+        computer.debugger.break_on_overflow = true;
+
+        computer.initiate_pressed();
+        computer.continue_to_completion().unwrap();
+        assert_eq!(
+            computer.status,
+            EdvacStatus::Halted {
+                resume_addr: 0o0001
+            }
+        );
+        assert_eq!(
+            computer.debugger.stops(),
+            [StopReason::Overflow { address: 0o0000 }]
+        );
+    }
+}
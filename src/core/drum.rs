@@ -0,0 +1,288 @@
+//! # Magnetic drum
+//! In the 1950s the EDVAC was given a magnetic drum as auxiliary memory, sitting
+//! between the delay lines and the wires: much bigger than the former and much
+//! quicker to get at than the latter. A [`Drum`] holds [`TRACKS`] tracks of
+//! [`WORDS_PER_TRACK`] words, and addresses on the drum run through each track
+//! in turn, so the drum address of a word is its track times
+//! [`WORDS_PER_TRACK`] plus its position on the track.
+//!
+//! The drum is optional, and a machine without one (the default, like the
+//! original machine) runs exactly as before.
+//!
+//! ## Transfer orders
+//! With a drum attached, a wire order with the [`DRUM_ORDER`] bit set in its
+//! sub-order moves words between high-speed memory and the drum instead:
+//! ```text
+//! W start sub-order end next
+//! ```
+//! Bits 6 and 7 of the sub-order select the operation like they do for the
+//! wires (`1` records from high-speed memory onto the drum, `2` reads from the
+//! drum into high-speed memory, and anything else halts), and the six bits that
+//! would select the spool and are otherwise unused select the track instead.
+//! The words from `start` to `end` go to or come from consecutive drum addresses
+//! starting at the beginning of that track, carrying on into the next track if
+//! needed. So `W 0100 0500 0107 0002` records 0100 to 0107 onto the first eight
+//! words of track 0, and `W 0100 0605 0107 0002` reads them back from track 5.
+//!
+//! ## Timing
+//! The drum turns continuously and every track passes its head once per
+//! revolution ([`Drum::revolution_time`]), so like the delay lines a word can
+//! only be transferred while it is passing the head. Each word waits for its
+//! position to come around (see [`Drum::latency`]) and then takes
+//! [`Drum::word_time`] to transfer. Waiting on high-speed memory in between can
+//! make a transfer miss the next position and wait a whole revolution for it,
+//! which is why drum programs were laid out with as much care as delay line
+//! ones.
+//!
+//! None of the sources at hand give how fast the EDVAC's drum turned, so no
+//! speed is built in: by default the drum takes no time at all, and
+//! [`Drum::word_time`] has to be set to time it.
+
+use crate::{error::EdvacError, observer::EdvacEvent, timing::SimulatedClock, word::Word, Edvac};
+
+use log::trace;
+
+pub const TRACKS: usize = 64;
+pub const WORDS_PER_TRACK: usize = 72;
+/// Number of words on the drum.
+pub const DRUM_SIZE: usize = TRACKS * WORDS_PER_TRACK;
+
+/// Sub-order bit that makes a wire order a drum transfer, when there is a drum.
+pub const DRUM_ORDER: usize = 0o400;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Drum {
+    /// Time for a word to pass the head, in pulse times; 0 by default (see
+    /// the [module documentation](self)).
+    pub word_time: u64,
+
+    words: Vec<Word>,
+}
+
+impl Default for Drum {
+    fn default() -> Self {
+        Drum {
+            word_time: 0,
+            words: vec![Word::from_bits(0); DRUM_SIZE],
+        }
+    }
+}
+
+impl Drum {
+    /// Time for one full turn of the drum, in pulse times.
+    #[must_use]
+    pub fn revolution_time(&self) -> u64 {
+        self.word_time * WORDS_PER_TRACK as u64
+    }
+
+    /// Number of pulse times until the word at drum address `address` starts
+    /// passing the head. Every track turns together, so this only depends on
+    /// the position of the word on its track.
+    #[must_use]
+    pub fn latency(&self, clock: &SimulatedClock, address: usize) -> u64 {
+        let revolution_time = self.revolution_time();
+        if revolution_time == 0 {
+            return 0;
+        }
+
+        let slot = (address % WORDS_PER_TRACK) as u64 * self.word_time;
+        let position = clock.pulse_times() % revolution_time;
+
+        (slot + revolution_time - position) % revolution_time
+    }
+
+    pub fn get(&self, address: usize) -> Result<Word, EdvacError> {
+        self.words
+            .get(address)
+            .copied()
+            .ok_or(EdvacError::DrumAddressOutOfRange { address })
+    }
+
+    pub fn set(&mut self, address: usize, word: Word) -> Result<(), EdvacError> {
+        let slot = self
+            .words
+            .get_mut(address)
+            .ok_or(EdvacError::DrumAddressOutOfRange { address })?;
+        *slot = word;
+
+        Ok(())
+    }
+
+    /// The words on track `track`, in order.
+    ///
+    /// # Panics
+    /// If there is no such track.
+    #[must_use]
+    pub fn track(&self, track: usize) -> &[Word] {
+        &self.words[track * WORDS_PER_TRACK..(track + 1) * WORDS_PER_TRACK]
+    }
+
+    pub fn load(&mut self, words: Vec<(usize, Word)>) -> Result<(), EdvacError> {
+        for (address, word) in words {
+            self.set(address, word)?;
+        }
+
+        Ok(())
+    }
+
+    /// Every word on the drum, by drum address.
+    #[must_use]
+    pub fn dump(&self) -> &[Word] {
+        &self.words
+    }
+}
+
+/// # Drum operations
+impl Edvac {
+    /// Charges the simulated clock for waiting on drum address `address` to
+    /// come around and then be transferred.
+    fn wait_for_drum(&mut self, address: usize) {
+        let drum = self.drum.as_ref().expect("drum orders need a drum");
+        let wait = drum.latency(&self.clock, address);
+        self.clock.advance(wait + drum.word_time);
+
+        trace!("Waited {} pulse times for drum {:0>5o}", wait, address);
+    }
+
+    pub(crate) fn read_word_from_drum(&mut self, address: usize) -> Result<Word, EdvacError> {
+        let drum = self.drum.as_ref().expect("drum orders need a drum");
+        let word = drum.get(address)?;
+        self.wait_for_drum(address);
+
+        trace!(
+            "Read Word off Drum {:0>5o}: {:0>44b}",
+            address,
+            word.get_bits()
+        );
+        self.notify(EdvacEvent::DrumRead { address, word });
+
+        Ok(word)
+    }
+
+    pub(crate) fn write_word_to_drum(
+        &mut self,
+        address: usize,
+        word: Word,
+    ) -> Result<(), EdvacError> {
+        let drum = self.drum.as_ref().expect("drum orders need a drum");
+        let prior = drum.get(address)?;
+        self.wait_for_drum(address);
+
+        trace!(
+            "Write Word to Drum {:0>5o}: {:0>44b}",
+            address,
+            word.get_bits()
+        );
+        self.journal.drum_changed(address, prior);
+        self.drum.as_mut().unwrap().set(address, word)?;
+        self.notify(EdvacEvent::DrumWrite { address, word });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::EdvacStatus;
+
+    /// Made up for the tests, not the EDVAC's.
+    const WORD_TIME: u64 = 232;
+
+    fn timed_drum() -> Drum {
+        Drum {
+            word_time: WORD_TIME,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn latency() {
+        let mut clock = SimulatedClock::default();
+        let drum = timed_drum();
+
+        assert_eq!(drum.latency(&clock, 3), 3 * WORD_TIME);
+        // the same position on any track
+        assert_eq!(drum.latency(&clock, WORDS_PER_TRACK + 3), 3 * WORD_TIME);
+
+        clock.advance(4 * WORD_TIME);
+        assert_eq!(drum.latency(&clock, 4), 0);
+        assert_eq!(drum.latency(&clock, 3), drum.revolution_time() - WORD_TIME);
+
+        // an untimed drum never keeps anything waiting
+        assert_eq!(Drum::default().latency(&clock, 3), 0);
+    }
+
+    #[test]
+    fn transfers() {
+        let mut computer = Edvac {
+            drum: Some(timed_drum()),
+            ..Default::default()
+        };
+        computer.journal.set_capacity(10);
+        computer
+            .high_speed_memory
+            .load(vec![
+                // record 0o0100 to 0o0102 onto track 5, then read them back
+                (0o0000, inst!(W 0o0100 0o0505 0o0102 0o0001)),
+                (0o0001, inst!(W 0o0200 0o0605 0o0202 0o0002)),
+                (0o0002, inst!(H 0o0000 0o0000 0o0000 0o0000)),
+                (0o0100, 1.try_into().unwrap()),
+                (0o0101, 2.try_into().unwrap()),
+                (0o0102, (-3).try_into().unwrap()),
+            ])
+            .unwrap();
+
+        computer.initiate_pressed();
+        computer.continue_to_completion().unwrap();
+
+        let track = computer.drum.as_ref().unwrap().track(5);
+        assert_eq!(
+            track[..3],
+            computer.high_speed_memory.dump()[0o0100..0o0103]
+        );
+        assert!(track[3..].iter().all(|word| word.get_bits() == 0));
+        assert_eq!(
+            computer.high_speed_memory.dump()[0o0200..0o0203],
+            computer.high_speed_memory.dump()[0o0100..0o0103]
+        );
+        // each transfer took at least most of a revolution
+        assert!(computer.clock.pulse_times() > 2 * timed_drum().revolution_time());
+
+        while computer.step_back() {}
+        assert_eq!(computer.drum, Some(timed_drum()));
+
+        // without a drum, the same orders are plain wire orders on Wire 1
+        let mut computer = Edvac::default();
+        computer
+            .high_speed_memory
+            .load(vec![(0o0000, inst!(W 0o0100 0o0501 0o0102 0o0001))])
+            .unwrap();
+        computer.initiate_pressed();
+        computer.step_once().unwrap();
+        assert_eq!(computer.low_speed_memory[0].extent(), 3 * 44);
+    }
+
+    #[test]
+    fn end_of_drum() {
+        let mut computer = Edvac {
+            drum: Some(Drum::default()),
+            ..Default::default()
+        };
+        computer
+            .high_speed_memory
+            .load(vec![
+                // the last track only has room for 72 of these
+                (0o0000, inst!(W 0o0100 0o0577 0o0300 0o0001)),
+            ])
+            .unwrap();
+
+        computer.initiate_pressed();
+        assert_eq!(
+            computer.continue_to_completion(),
+            Err(EdvacError::DrumAddressOutOfRange { address: DRUM_SIZE })
+        );
+        assert!(matches!(computer.status, EdvacStatus::Faulted { .. }));
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+
+use crate::{orders::OrderKind, wire::WireSpool};
+
+/// Conditions that stop the machine in its tracks. When one of these comes up
+/// while running, the EDVAC is put into [`crate::EdvacStatus::Faulted`] rather
+/// than taking the host program down with it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EdvacError {
+    /// An address that doesn't exist in the 1024 words of high-speed memory.
+    /// Addresses decoded from orders are always in range, so this only comes
+    /// from the console switches or direct use of the library.
+    AddressOutOfRange { address: usize },
+    /// A wire order asked for a spool that doesn't exist.
+    InvalidWireSpool { spool_id: u64 },
+    /// Wire 0 is not a real wire, so addresses can't be read off of it.
+    ReadAddressFromWire { spool: WireSpool },
+    /// A wire was run off of its end, by moving it or by reading or recording
+    /// past the last bit. See [`crate::transport::WireEndAction`].
+    EndOfWire { spool: WireSpool },
+    /// A wire was run backward off of its beginning.
+    BeginningOfWire { spool: WireSpool },
+    /// A drum transfer ran past the last word of the drum.
+    DrumAddressOutOfRange { address: usize },
+    /// The card unit standing in for `spool` ran out of cards to read.
+    CardHopperEmpty { spool: WireSpool },
+    /// A card doesn't hold words in the format the card unit standing in for
+    /// `spool` is set to.
+    UnreadableCard { spool: WireSpool },
+    /// With [`crate::Edvac::arithmetic_check`] on, the two implementations of
+    /// `order` came up with different results. See [`crate::arithmetic`].
+    ArithmeticCheckFailure { order: OrderKind },
+}
+
+impl fmt::Display for EdvacError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EdvacError::AddressOutOfRange { address } => {
+                write!(f, "address {:o} is out of range", address)
+            }
+            EdvacError::InvalidWireSpool { spool_id } => {
+                write!(f, "there is no wire spool {}", spool_id)
+            }
+            EdvacError::ReadAddressFromWire { spool } => {
+                write!(f, "cannot read an address from Wire {:?}", spool)
+            }
+            EdvacError::EndOfWire { spool } => write!(f, "Wire {:?} ran off of its end", spool),
+            EdvacError::BeginningOfWire { spool } => {
+                write!(f, "Wire {:?} ran off of its beginning", spool)
+            }
+            EdvacError::DrumAddressOutOfRange { address } => {
+                write!(f, "drum address {:o} is out of range", address)
+            }
+            EdvacError::CardHopperEmpty { spool } => {
+                write!(f, "the card hopper on Wire {:?} is empty", spool)
+            }
+            EdvacError::UnreadableCard { spool } => {
+                write!(f, "a card on Wire {:?} can't be read", spool)
+            }
+            EdvacError::ArithmeticCheckFailure { order } => {
+                write!(f, "arithmetic check failed on a {:?} order", order)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EdvacError {}
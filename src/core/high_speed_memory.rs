@@ -1,10 +1,13 @@
-use crate::{operating_console::MemoryMode, word::Word};
+use crate::{error::EdvacError, operating_console::MemoryMode, word::Word};
 
 // Most of this comes from Origins+Fate pg. 34
 
 pub const ADDRESS_WIDTH: usize = 10;
 pub const ADDRESS_MASK: u64 = 2_u64.pow(ADDRESS_WIDTH as u32) - 1;
 
+/// Words are stored 8 to a mercury tank, in the order of their addresses.
+pub const WORDS_PER_TANK: usize = 8;
+
 pub struct HighSpeedMemory {
     bank: [Word; 1024],
 }
@@ -18,42 +21,39 @@ impl Default for HighSpeedMemory {
 }
 
 impl HighSpeedMemory {
-    #[must_use]
-    pub fn get(&self, addr: usize, mode: MemoryMode) -> Word {
-        assert!(addr < 1024);
-
-        match mode {
-            MemoryMode::L0 => {
-                // Per FuncDesc pg. "5-12"
-                // these "special" memory modes enforce a specific tank "without
-                // regard to the presence or absense of a pulse at 2^9"
-                self.bank[addr % 512]
-            }
-            MemoryMode::LR => self.bank[addr],
-            MemoryMode::R1 => self.bank[(addr % 512) + 512],
+    /// Maps an address, as seen by the program, to the index of the word that
+    /// actually gets used under the given memory mode.
+    pub fn physical_address(addr: usize, mode: MemoryMode) -> Result<usize, EdvacError> {
+        if addr >= 1024 {
+            return Err(EdvacError::AddressOutOfRange { address: addr });
         }
+
+        Ok(match mode {
+            // Per FuncDesc pg. "5-12"
+            // these "special" memory modes enforce a specific tank "without
+            // regard to the presence or absense of a pulse at 2^9"
+            MemoryMode::L0 => addr % 512,
+            MemoryMode::LR => addr,
+            MemoryMode::R1 => (addr % 512) + 512,
+        })
     }
 
-    pub fn set(&mut self, addr: usize, mode: MemoryMode, val: Word) {
-        assert!(addr < 1024);
-
-        match mode {
-            MemoryMode::L0 => {
-                self.bank[addr % 512] = val;
-            }
-            MemoryMode::LR => {
-                self.bank[addr] = val;
-            }
-            MemoryMode::R1 => {
-                self.bank[(addr % 512) + 512] = val;
-            }
-        }
+    pub fn get(&self, addr: usize, mode: MemoryMode) -> Result<Word, EdvacError> {
+        Ok(self.bank[HighSpeedMemory::physical_address(addr, mode)?])
     }
 
-    pub fn load(&mut self, words: Vec<(usize, Word)>) {
+    pub fn set(&mut self, addr: usize, mode: MemoryMode, val: Word) -> Result<(), EdvacError> {
+        self.bank[HighSpeedMemory::physical_address(addr, mode)?] = val;
+
+        Ok(())
+    }
+
+    pub fn load(&mut self, words: Vec<(usize, Word)>) -> Result<(), EdvacError> {
         for (addr, word) in words {
-            self.set(addr, MemoryMode::default(), word);
+            self.set(addr, MemoryMode::default(), word)?;
         }
+
+        Ok(())
     }
 
     #[must_use]
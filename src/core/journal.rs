@@ -0,0 +1,279 @@
+//! # Journal
+//! Keeps enough history to run the machine backwards. Each time the machine is
+//! stepped (an order, a cycle or execute phase, a special order, ...) an entry
+//! is made holding the registers, the simulated clock, the wire transports and
+//! the position of the card unit as they were before, along with the prior value
+//! of every memory word, wire bit and drum word the step changed.
+//! [`Edvac::step_back`] then undoes the steps one at a time.
+//!
+//! Only the most recent [`Journal::capacity`] steps are kept. The journal starts
+//! out with a capacity of zero, which turns it off entirely.
+
+use std::collections::VecDeque;
+
+use crate::{
+    operating_console::{MemoryMode, State},
+    timing::SimulatedClock,
+    transport::WireTransport,
+    word::Word,
+    Edvac, EdvacStatus,
+};
+
+struct WireChange {
+    spool: usize,
+    index: usize,
+    bits: u64,
+    length: usize,
+}
+
+struct Entry {
+    state: State,
+    status: EdvacStatus,
+    clock: SimulatedClock,
+    wire_indices: [usize; 3],
+    wire_extents: [usize; 3],
+    wire_transports: [WireTransport; 3],
+    typewriter_length: usize,
+    card_position: Option<(usize, usize)>,
+
+    /// Prior values of memory words, by physical address, in the order they
+    /// were changed.
+    memory: Vec<(usize, Word)>,
+    wires: Vec<WireChange>,
+    /// Prior values of drum words, by drum address.
+    drum: Vec<(usize, Word)>,
+}
+
+#[derive(Default)]
+pub struct Journal {
+    entries: VecDeque<Entry>,
+    capacity: usize,
+
+    /// The entry for the step in progress, if any.
+    open: Option<Entry>,
+}
+
+impl Journal {
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Journal {
+        Journal {
+            capacity,
+            ..Default::default()
+        }
+    }
+
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes how many steps are kept, dropping the oldest ones if needed.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+
+        while self.entries.len() > capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Number of steps that can currently be undone.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.open = None;
+    }
+
+    pub(crate) fn commit(&mut self) {
+        if let Some(entry) = self.open.take() {
+            if self.entries.len() == self.capacity {
+                self.entries.pop_front();
+            }
+
+            self.entries.push_back(entry);
+        }
+    }
+
+    pub(crate) fn memory_changed(&mut self, physical_address: usize, prior: Word) {
+        if let Some(entry) = &mut self.open {
+            entry.memory.push((physical_address, prior));
+        }
+    }
+
+    pub(crate) fn drum_changed(&mut self, address: usize, prior: Word) {
+        if let Some(entry) = &mut self.open {
+            entry.drum.push((address, prior));
+        }
+    }
+
+    pub(crate) fn wire_changed(&mut self, spool: usize, index: usize, bits: u64, length: usize) {
+        if let Some(entry) = &mut self.open {
+            entry.wires.push(WireChange {
+                spool,
+                index,
+                bits,
+                length,
+            });
+        }
+    }
+}
+
+/// # Reverse execution
+impl Edvac {
+    /// Opens a journal entry for a new step, unless the journal is off or a
+    /// step is already in progress (for example, when an order executes another
+    /// order). Returns whether an entry was opened.
+    pub(crate) fn begin_step(&mut self) -> bool {
+        if self.journal.capacity == 0 || self.journal.open.is_some() {
+            return false;
+        }
+
+        let wires = &self.low_speed_memory;
+        self.journal.open = Some(Entry {
+            state: self.state.clone(),
+            status: self.status,
+            clock: self.clock,
+            wire_indices: [wires[0].index(), wires[1].index(), wires[2].index()],
+            wire_extents: [wires[0].extent(), wires[1].extent(), wires[2].extent()],
+            wire_transports: self.wire_transports,
+            typewriter_length: self.typewriter.output().len(),
+            card_position: self.card_unit.as_ref().map(|unit| unit.position()),
+            memory: Vec::new(),
+            wires: Vec::new(),
+            drum: Vec::new(),
+        });
+
+        true
+    }
+
+    /// Undoes the most recent step recorded in the [`Journal`], returning
+    /// `false` if there was nothing to undo.
+    ///
+    /// Everything the step changed is put back as it was, except that a machine
+    /// which was running is left halted on the order that was undone, so that
+    /// pressing Initiate runs it again.
+    pub fn step_back(&mut self) -> bool {
+        let entry = match self.journal.entries.pop_back() {
+            Some(entry) => entry,
+            None => return false,
+        };
+
+        for &(physical_address, word) in entry.memory.iter().rev() {
+            // LR leaves addresses as they are, so this is the physical address
+            self.high_speed_memory
+                .set(physical_address, MemoryMode::LR, word)
+                .unwrap();
+        }
+
+        for change in entry.wires.iter().rev() {
+            self.low_speed_memory[change.spool].set_bits_at(
+                change.index,
+                change.bits,
+                change.length,
+            );
+        }
+
+        if let Some(drum) = &mut self.drum {
+            for &(address, word) in entry.drum.iter().rev() {
+                drum.set(address, word).unwrap();
+            }
+        }
+
+        for (i, wire) in self.low_speed_memory.iter_mut().enumerate() {
+            wire.seek(entry.wire_indices[i]);
+            wire.set_extent(entry.wire_extents[i]);
+        }
+
+        self.state = entry.state;
+        self.clock = entry.clock;
+        self.wire_transports = entry.wire_transports;
+        // paper can't be untyped, but this is an emulator
+        self.typewriter.truncate(entry.typewriter_length);
+        // neither can cards be unpunched
+        if let (Some(unit), Some(position)) = (&mut self.card_unit, entry.card_position) {
+            unit.set_position(position);
+        }
+        self.status = match entry.status {
+            EdvacStatus::Running => EdvacStatus::Halted {
+                resume_addr: self.state.initial_address_register,
+            },
+            status => status,
+        };
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{test_programs::dec_to_bin, word::Word};
+
+    fn snapshot(computer: &Edvac) -> Vec<u8> {
+        let mut snapshot = Vec::new();
+        computer.save_snapshot(&mut snapshot).unwrap();
+
+        snapshot
+    }
+
+    #[test]
+    fn step_back() {
+        let mut computer = Edvac::default();
+        computer.journal.set_capacity(10_000);
+        dec_to_bin::load(&mut computer);
+        computer.low_speed_memory[1].write_word(Word::from_bits(
+            0b0010_0010_0010_0010_0010_0010_0010_0010_0010_0010_0000,
+        ));
+
+        computer.initiate_pressed();
+        for _ in 0..10 {
+            computer.step_once().unwrap();
+        }
+        let before = snapshot(&computer);
+
+        let mut steps = 0;
+        while computer.status == EdvacStatus::Running {
+            computer.step_once().unwrap();
+            steps += 1;
+        }
+        assert_eq!(computer.journal.len(), 10 + steps);
+
+        for _ in 0..steps {
+            assert!(computer.step_back());
+        }
+        assert_eq!(
+            computer.status,
+            EdvacStatus::Halted {
+                resume_addr: computer.state.initial_address_register
+            }
+        );
+
+        computer.initiate_pressed();
+        assert_eq!(snapshot(&computer), before);
+    }
+
+    #[test]
+    fn capacity() {
+        let mut computer = Edvac::default();
+        computer.journal.set_capacity(3);
+        dec_to_bin::load(&mut computer);
+
+        computer.initiate_pressed();
+        computer.continue_to_completion().unwrap();
+        assert_eq!(computer.journal.len(), 3);
+
+        for _ in 0..3 {
+            assert!(computer.step_back());
+        }
+        assert!(!computer.step_back());
+    }
+}
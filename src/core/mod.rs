@@ -1,9 +1,22 @@
+pub mod error;
 pub mod high_speed_memory;
 pub mod operating_console;
 #[macro_use]
 pub mod order_macros;
+pub mod arithmetic;
+pub mod cards;
 pub mod computer;
+pub mod debugger;
+pub mod drum;
+pub mod journal;
+pub mod observer;
 pub mod orders;
+pub mod serial;
+pub mod snapshot;
+pub mod timing;
+pub mod trace;
+pub mod transport;
+pub mod typewriter;
 pub mod wire;
 pub mod word;
 
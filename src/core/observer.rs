@@ -0,0 +1,239 @@
+//! # Observers
+//! Profilers, tracers and the like can follow along with the machine by
+//! attaching an [`EdvacObserver`] with [`Edvac::attach_observer`]. The core
+//! calls every attached observer with an [`EdvacEvent`] as things happen, in the
+//! order they happen.
+//!
+//! Observers only see what the machine does; they can't change it. Closures
+//! taking an `&EdvacEvent` are observers too.
+
+use crate::{
+    cards::Card,
+    error::EdvacError,
+    orders::{Order, OrderSource},
+    wire::{WireShift, WireSpool},
+    word::Word,
+    Edvac,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EdvacEvent {
+    /// An order was transferred into the order register (its cycle phase).
+    /// `address` is that of the order for orders from memory, and the contents
+    /// of the Initial Address Register otherwise.
+    OrderFetched {
+        address: usize,
+        source: OrderSource,
+        order: Order,
+    },
+    /// An order was carried out. `address` is the contents of the Initial
+    /// Address Register when it started.
+    OrderExecuted {
+        address: usize,
+        order: Order,
+    },
+
+    MemoryRead {
+        address: usize,
+        word: Word,
+    },
+    MemoryWrite {
+        address: usize,
+        word: Word,
+    },
+
+    /// Reading a word from [`WireSpool::Zero`] reads the Auxiliary Input
+    /// switches.
+    WireRead {
+        spool: WireSpool,
+        word: Word,
+    },
+    WireAddressRead {
+        spool: WireSpool,
+        address: usize,
+    },
+    WireWrite {
+        spool: WireSpool,
+        word: Word,
+    },
+    WireTranslated {
+        spool: WireSpool,
+        shift: WireShift,
+    },
+
+    /// `address` is a drum address; see [`crate::drum`].
+    DrumRead {
+        address: usize,
+        word: Word,
+    },
+    DrumWrite {
+        address: usize,
+        word: Word,
+    },
+
+    /// A card was fed through the card unit standing in for `spool`; see
+    /// [`crate::cards`].
+    CardRead {
+        spool: WireSpool,
+        card: Card,
+    },
+    CardPunched {
+        spool: WireSpool,
+        card: Card,
+    },
+
+    /// The order at `address` exceeded the capacity of the machine.
+    Overflow {
+        address: usize,
+        division: bool,
+    },
+    /// Comes after the [`EdvacEvent::OrderExecuted`] of an order that halted
+    /// the machine.
+    Halted {
+        resume_addr: usize,
+    },
+    Faulted {
+        resume_addr: usize,
+        error: EdvacError,
+    },
+}
+
+pub trait EdvacObserver {
+    fn notify(&mut self, event: &EdvacEvent);
+}
+
+impl<F: FnMut(&EdvacEvent)> EdvacObserver for F {
+    fn notify(&mut self, event: &EdvacEvent) {
+        self(event);
+    }
+}
+
+/// # Observers
+impl Edvac {
+    pub fn attach_observer(&mut self, observer: Box<dyn EdvacObserver + Send>) {
+        self.observers.push(observer);
+    }
+
+    /// Detaches every observer, handing them back in the order they were
+    /// attached.
+    pub fn detach_observers(&mut self) -> Vec<Box<dyn EdvacObserver + Send>> {
+        std::mem::take(&mut self.observers)
+    }
+
+    pub(crate) fn notify(&mut self, event: EdvacEvent) {
+        for observer in &mut self.observers {
+            observer.notify(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[test]
+    fn events() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+
+        let add = inst!(A 0o0010 0o0010 0o0011 0o0001);
+        let halt = inst!(H 0o0000 0o0000 0o0000 0o0002);
+        let one: Word = 1.try_into().unwrap();
+        let two: Word = 2.try_into().unwrap();
+
+        let mut computer = Edvac::default();
+        computer
+            .high_speed_memory
+            .load(vec![(0o0000, add), (0o0001, halt), (0o0010, one)])
+            .unwrap();
+
+        let recorder = Arc::clone(&events);
+        computer.attach_observer(Box::new(move |event: &EdvacEvent| {
+            recorder.lock().unwrap().push(*event);
+        }));
+
+        computer.initiate_pressed();
+        computer.continue_to_completion().unwrap();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                EdvacEvent::MemoryRead {
+                    address: 0o0000,
+                    word: add,
+                },
+                EdvacEvent::OrderFetched {
+                    address: 0o0000,
+                    source: OrderSource::Memory,
+                    order: add.into(),
+                },
+                EdvacEvent::MemoryRead {
+                    address: 0o0010,
+                    word: one,
+                },
+                EdvacEvent::MemoryRead {
+                    address: 0o0010,
+                    word: one,
+                },
+                EdvacEvent::MemoryWrite {
+                    address: 0o0011,
+                    word: two,
+                },
+                EdvacEvent::OrderExecuted {
+                    address: 0o0000,
+                    order: add.into(),
+                },
+                EdvacEvent::MemoryRead {
+                    address: 0o0001,
+                    word: halt,
+                },
+                EdvacEvent::OrderFetched {
+                    address: 0o0001,
+                    source: OrderSource::Memory,
+                    order: halt.into(),
+                },
+                EdvacEvent::OrderExecuted {
+                    address: 0o0001,
+                    order: halt.into(),
+                },
+                EdvacEvent::Halted {
+                    resume_addr: 0o0002
+                },
+            ]
+        );
+
+        assert_eq!(computer.detach_observers().len(), 1);
+    }
+
+    #[test]
+    fn one_event_per_fault() {
+        use crate::wire::WIRE_SIZE;
+        use crate::word::BIT_WIDTH;
+
+        let faults = Arc::new(Mutex::new(0));
+
+        let mut computer = Edvac::default();
+        computer
+            .high_speed_memory
+            .load(vec![(0o0000, inst!(W 0o0010 0o0201 0o0013 0o0001))])
+            .unwrap();
+        computer.low_speed_memory[0].seek(WIRE_SIZE - 2 * BIT_WIDTH);
+
+        let counter = Arc::clone(&faults);
+        computer.attach_observer(Box::new(move |event: &EdvacEvent| {
+            if let EdvacEvent::Faulted { .. } = event {
+                *counter.lock().unwrap() += 1;
+            }
+        }));
+
+        computer.initiate_pressed();
+        assert_eq!(
+            computer.step_once(),
+            Err(EdvacError::EndOfWire {
+                spool: WireSpool::One
+            })
+        );
+        assert_eq!(*faults.lock().unwrap(), 1);
+    }
+}
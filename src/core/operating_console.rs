@@ -1,15 +1,15 @@
-use crate::word::Word;
+use crate::{orders::CycledOrder, word::Word};
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum OperatingMode {
-    // SpecialOneCycle,
-    // SpecialOneExecute,
+    SpecialOneCycle,
+    SpecialOneExecute,
     SpecialOneOrder,
 
     NormalToCompletion,
     NormalToAddressA,
-    // NormalOneCycle,
-    // NormalOneExecute,
+    NormalOneCycle,
+    NormalOneExecute,
     NormalOneOrder,
 }
 
@@ -56,8 +56,11 @@ impl Default for ExcessCapacityAction {
     }
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub struct State {
     pub initial_address_register: usize,
+    /// Holds an order between its cycle and execute phases.
+    pub order_register: Option<CycledOrder>,
     pub operating_mode: OperatingMode,
 
     pub excess_capacity_action_add: ExcessCapacityAction,
@@ -75,6 +78,7 @@ impl Default for State {
     fn default() -> Self {
         State {
             initial_address_register: 0,
+            order_register: None,
             operating_mode: Default::default(),
 
             excess_capacity_action_add: Default::default(),
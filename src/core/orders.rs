@@ -1,12 +1,17 @@
 use crate::{
+    arithmetic,
+    drum::{DRUM_ORDER, WORDS_PER_TRACK},
+    error::EdvacError,
     high_speed_memory::{ADDRESS_MASK, ADDRESS_WIDTH},
+    observer::EdvacEvent,
     operating_console::ExcessCapacityAction,
+    transport::WireActivity,
     wire::WireShift,
     word::{Word, BIT_WIDTH, U43_MAX},
-    Edvac,
+    Edvac, EdvacStatus,
 };
 
-use log::debug;
+use log::{debug, trace};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum OrderKind {
@@ -44,6 +49,25 @@ impl OrderKind {
         }
     }
 
+    /// Opposite of [`OrderKind::from_mneumonic`]; `None` for unused orders.
+    #[must_use]
+    pub fn to_mneumonic(self) -> Option<&'static str> {
+        match self {
+            Self::Compare => Some("C"),
+            Self::ManualRead => Some("MR"),
+            Self::Add => Some("A"),
+            Self::Wire => Some("W"),
+            Self::Sub => Some("S"),
+            Self::Extract => Some("E"),
+            Self::Mul => Some("M"),
+            Self::MulExact => Some("m"),
+            Self::Div => Some("D"),
+            Self::DivExact => Some("d"),
+            Self::Halt => Some("H"),
+            Self::Unused => None,
+        }
+    }
+
     #[must_use]
     pub fn to_bits(self) -> u64 {
         // Opposite of the From<Word> operation below, see comments there.
@@ -96,7 +120,7 @@ impl From<Word> for OrderKind {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Order {
     pub kind: OrderKind,
     pub addresses: [usize; 4],
@@ -124,8 +148,104 @@ impl From<Word> for Order {
     }
 }
 
+/// Where an order in the order register was cycled in from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OrderSource {
+    Memory,
+    SpecialOrderSwitches,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CycledOrder {
+    pub order: Order,
+    pub source: OrderSource,
+}
+
+/// # Cycle and execute phases
+/// Every order is carried out in two phases. During the *cycle* phase the order
+/// is transferred into the order register, and during the *execute* phase the
+/// order in the register is carried out. Normally the two follow each other
+/// without pause, but the One Cycle and One Execute modes of operation stop the
+/// machine in between.
 impl Edvac {
-    fn handle_overflow(&mut self, is_div: bool, resume_addr: usize) {
+    /// Transfers an order into the order register, replacing whatever was there.
+    /// If a breakpoint on an order in memory is hit, the machine halts instead
+    /// (see [`crate::debugger`]).
+    pub fn cycle(&mut self, source: OrderSource) -> Result<(), EdvacError> {
+        if source == OrderSource::Memory
+            && self.status == EdvacStatus::Running
+            && self.debugger.breakpoint_hit(
+                self.state.initial_address_register,
+                &self.high_speed_memory,
+                self.state.memory_mode,
+            )
+        {
+            self.halt_pressed();
+
+            return Ok(());
+        }
+
+        let order: Order = match source {
+            OrderSource::Memory => self.get(self.state.initial_address_register)?.into(),
+            OrderSource::SpecialOrderSwitches => self.state.special_order_switches.into(),
+        };
+
+        trace!("Cycle ({:?}): {:?}", source, order);
+        self.notify(EdvacEvent::OrderFetched {
+            address: self.state.initial_address_register,
+            source,
+            order,
+        });
+
+        self.state.order_register = Some(CycledOrder { order, source });
+
+        Ok(())
+    }
+
+    /// Executes the order in the order register, emptying it. Returns `false`
+    /// if there was no order to execute.
+    pub fn execute_cycled(&mut self) -> Result<bool, EdvacError> {
+        let cycled = match self.state.order_register.take() {
+            Some(cycled) => cycled,
+            None => return Ok(false),
+        };
+
+        match cycled.source {
+            OrderSource::Memory => self.execute_once(&cycled.order)?,
+            OrderSource::SpecialOrderSwitches => {
+                // save current execution address
+                let old_address = self.state.initial_address_register;
+
+                self.execute_once(&cycled.order)?;
+
+                // the current fourth-address value of the special order
+                // instruction was saved to the IAR- the EDVAC does not do this,
+                // so we restore the saved execution address from before we ran
+                // the order and set the machine to Halt, per FuncDesc pg. "3-9"
+                let next_address = self.state.initial_address_register;
+                self.state.initial_address_register = old_address;
+
+                self.halt(next_address);
+            }
+        }
+
+        if self.status == EdvacStatus::Running && self.debugger.should_stop() {
+            self.halt_pressed();
+        }
+
+        Ok(true)
+    }
+}
+
+impl Edvac {
+    fn handle_overflow(&mut self, is_div: bool, resume_addr: usize) -> Result<(), EdvacError> {
+        self.debugger
+            .overflowed(self.state.initial_address_register);
+        self.notify(EdvacEvent::Overflow {
+            address: self.state.initial_address_register,
+            division: is_div,
+        });
+
         let action = if is_div {
             self.state.excess_capacity_action_div
         } else {
@@ -140,20 +260,27 @@ impl Edvac {
                 self.state.initial_address_register = resume_addr;
             }
             ExcessCapacityAction::ExecuteSpecial => {
-                self.execute_once(&self.state.special_order_switches.into());
+                self.execute_once(&self.state.special_order_switches.into())?;
                 self.state.initial_address_register = resume_addr; // overwrite!
             }
             ExcessCapacityAction::ExecuteAddressB => {
-                self.execute_once(&self.get(self.state.address_b_switches).into());
+                let order = self.get(self.state.address_b_switches)?.into();
+                self.execute_once(&order)?;
                 self.state.initial_address_register = resume_addr; // overwrite!
             }
         }
+
+        Ok(())
     }
 
-    fn execute_compare(&mut self, addresses: [usize; 4]) {
-        let a = self.get(addresses[0]);
-        let b = self.get(addresses[1]);
-        let (difference, _did_overflow) = a.overflowing_sub(b);
+    fn execute_compare(&mut self, addresses: [usize; 4]) -> Result<(), EdvacError> {
+        let a = self.get(addresses[0])?;
+        let b = self.get(addresses[1])?;
+        let (difference, _did_overflow) = self.checked(
+            OrderKind::Compare,
+            self.arithmetic_backend.overflowing_sub(a, b),
+            || arithmetic::overflowing_sub(a, b),
+        )?;
 
         let resume_addr = if difference.is_negative() {
             addresses[2]
@@ -162,37 +289,53 @@ impl Edvac {
         };
 
         self.state.initial_address_register = resume_addr;
+
+        Ok(())
     }
 
-    fn execute_manual_read(&mut self, addresses: [usize; 4]) {
+    fn execute_manual_read(&mut self, addresses: [usize; 4]) -> Result<(), EdvacError> {
         let value = Word::from_bits(self.state.auxiliary_input_switches.get_bits());
 
-        self.set(addresses[0], value);
-        self.set(addresses[1], value);
-        self.set(addresses[2], value);
+        self.set(addresses[0], value)?;
+        self.set(addresses[1], value)?;
+        self.set(addresses[2], value)?;
 
         self.state.initial_address_register = addresses[3];
+
+        Ok(())
     }
 
-    fn execute_add(&mut self, addresses: [usize; 4]) {
-        let a = self.get(addresses[0]);
-        let b = self.get(addresses[1]);
-        let (sum, did_overflow) = a.overflowing_add(b);
+    fn execute_add(&mut self, addresses: [usize; 4]) -> Result<(), EdvacError> {
+        let a = self.get(addresses[0])?;
+        let b = self.get(addresses[1])?;
+        let (sum, did_overflow) = self.checked(
+            OrderKind::Add,
+            self.arithmetic_backend.overflowing_add(a, b),
+            || arithmetic::overflowing_add(a, b),
+        )?;
 
-        self.set(addresses[2], sum);
+        self.set(addresses[2], sum)?;
         if did_overflow {
-            self.handle_overflow(false, addresses[3]);
+            self.handle_overflow(false, addresses[3])
         } else {
             self.state.initial_address_register = addresses[3];
+
+            Ok(())
         }
     }
 
-    fn execute_wire(&mut self, addresses: [usize; 4]) {
+    fn execute_wire(&mut self, addresses: [usize; 4]) -> Result<(), EdvacError> {
         let start = addresses[0];
         let sub_order = addresses[1];
         let end = addresses[2];
 
         let next_addr = addresses[3];
+
+        // not part of the original machine; see crate::drum
+        if sub_order & DRUM_ORDER != 0 && self.drum.is_some() {
+            return self.execute_drum(addresses);
+        }
+
         // Decoding for the sub-order is clearly described in FuncDesc pg "6-16"
         // section 6.3.7
 
@@ -214,16 +357,37 @@ impl Edvac {
 
         if backward && operation == 0o3 || spool_id == 0 && operation == 0o0 {
             self.halt(next_addr);
-            return;
+            return Ok(());
+        }
+
+        let wire_spool =
+            (spool_id as u64)
+                .try_into()
+                .map_err(|()| EdvacError::InvalidWireSpool {
+                    spool_id: spool_id as u64,
+                })?;
+
+        // not part of the original machine; see crate::cards
+        if self.card_unit_on(wire_spool) {
+            if backward {
+                self.halt(next_addr);
+                return Ok(());
+            }
+
+            return self.execute_cards(operation, addresses);
         }
 
-        let wire_spool = (spool_id as u64).try_into().unwrap();
+        let activity = match operation {
+            0o0 => WireActivity::Translating,
+            0o1 => WireActivity::Recording,
+            _ => WireActivity::Reading,
+        };
 
         // FuncDesc Diagram 104-4LC-3 "Wire Order Selector"
         let mut mem_index = start;
         loop {
             if backward {
-                self.translate_wire(wire_spool, WireShift::Backward(BIT_WIDTH));
+                self.translate_wire(wire_spool, WireShift::Backward(BIT_WIDTH), activity)?;
             }
 
             match operation {
@@ -232,32 +396,32 @@ impl Edvac {
                 }
                 0o1 => {
                     // Record (Memory -> Wire)
-                    let word = self.get(mem_index);
-                    self.write_word_to_wire(wire_spool, word);
+                    let word = self.get(mem_index)?;
+                    self.write_word_to_wire(wire_spool, word)?;
                 }
                 0o2 => {
                     // Read (Wire -> Memory)
-                    let word = self.read_word_from_wire(wire_spool);
-                    self.set(mem_index, word);
+                    let word = self.read_word_from_wire(wire_spool)?;
+                    self.set(mem_index, word)?;
                 }
                 0o3 => {
                     // Read 5th Addr (a.k.a. R5A)
-                    mem_index = self.read_address_from_wire(wire_spool);
-                    self.translate_wire(wire_spool, WireShift::Forward(ADDRESS_WIDTH));
-                    let word = self.read_word_from_wire(wire_spool);
-                    self.set(mem_index, word);
+                    mem_index = self.read_address_from_wire(wire_spool)?;
+                    self.translate_wire(wire_spool, WireShift::Forward(ADDRESS_WIDTH), activity)?;
+                    let word = self.read_word_from_wire(wire_spool)?;
+                    self.set(mem_index, word)?;
                 }
                 _ => unreachable!(),
             }
 
             if !backward {
-                self.translate_wire(wire_spool, WireShift::Forward(BIT_WIDTH));
+                self.translate_wire(wire_spool, WireShift::Forward(BIT_WIDTH), activity)?;
             }
 
             if mem_index == end {
                 self.state.initial_address_register = addresses[3];
 
-                return;
+                return Ok(());
             } else {
                 // this does nothing if operation == read fifth address, because
                 // mem_index gets reset later
@@ -266,27 +430,75 @@ impl Edvac {
         }
     }
 
-    fn execute_sub(&mut self, addresses: [usize; 4]) {
-        let a = self.get(addresses[0]);
-        let b = self.get(addresses[1]);
-        let (difference, did_overflow) = a.overflowing_sub(b);
+    /// A wire order with [`DRUM_ORDER`] set in its sub-order, when there is a
+    /// drum; see [`crate::drum`].
+    fn execute_drum(&mut self, addresses: [usize; 4]) -> Result<(), EdvacError> {
+        let start = addresses[0];
+        let sub_order = addresses[1];
+        let end = addresses[2];
+        let next_addr = addresses[3];
 
-        self.set(addresses[2], difference);
+        // same operation bits as the wire orders, and the spool bits pick a
+        // track instead
+        let operation = (sub_order >> 6) & 0b011;
+        let track = sub_order & 0o77;
+
+        if operation != 0o1 && operation != 0o2 {
+            self.halt(next_addr);
+            return Ok(());
+        }
+
+        let mut mem_index = start;
+        let mut drum_address = track * WORDS_PER_TRACK;
+        loop {
+            if operation == 0o1 {
+                // Record (Memory -> Drum)
+                let word = self.get(mem_index)?;
+                self.write_word_to_drum(drum_address, word)?;
+            } else {
+                // Read (Drum -> Memory)
+                let word = self.read_word_from_drum(drum_address)?;
+                self.set(mem_index, word)?;
+            }
+
+            if mem_index == end {
+                self.state.initial_address_register = next_addr;
+
+                return Ok(());
+            }
+
+            mem_index = (mem_index + 1) & ADDRESS_MASK as usize;
+            drum_address += 1;
+        }
+    }
+
+    fn execute_sub(&mut self, addresses: [usize; 4]) -> Result<(), EdvacError> {
+        let a = self.get(addresses[0])?;
+        let b = self.get(addresses[1])?;
+        let (difference, did_overflow) = self.checked(
+            OrderKind::Sub,
+            self.arithmetic_backend.overflowing_sub(a, b),
+            || arithmetic::overflowing_sub(a, b),
+        )?;
+
+        self.set(addresses[2], difference)?;
 
         if did_overflow {
-            self.handle_overflow(false, addresses[3]);
+            self.handle_overflow(false, addresses[3])
         } else {
             self.state.initial_address_register = addresses[3];
+
+            Ok(())
         }
     }
 
-    fn execute_extract(&mut self, addresses: [usize; 4]) {
-        let mut a = self.get(addresses[0]).get_bits();
+    fn execute_extract(&mut self, addresses: [usize; 4]) -> Result<(), EdvacError> {
+        let mut a = self.get(addresses[0])?.get_bits();
         let stored_sign = a & 0b1;
         a &= !0b1;
 
         let dest = addresses[2];
-        let mut result = self.get(dest).get_bits();
+        let mut result = self.get(dest)?.get_bits();
 
         let shift_code = addresses[1];
         let sub_order_code = shift_code & 0b111;
@@ -318,61 +530,91 @@ impl Edvac {
 
         result = (result & !mask) | (shifted & mask);
 
-        // post-processing/suborder specifics
+        // post-processing/suborder specifics: the whole word keeps its own
+        // sign rather than whatever was shifted into its place, but like any
+        // other number the machine forms, a zero comes out as +0
         if sub_order_code == 0o7 {
-            result |= stored_sign;
+            result = (result & !0b1) | stored_sign;
+            if result >> 1 == 0 {
+                result = 0;
+            }
         }
 
-        self.set(dest, Word::from_bits(result));
+        self.set(dest, Word::from_bits(result))?;
 
         self.state.initial_address_register = addresses[3];
+
+        Ok(())
     }
 
-    fn execute_mul(&mut self, addresses: [usize; 4], exact: bool) {
-        let a = self.get(addresses[0]);
-        let b = self.get(addresses[1]);
-        let (rounded, extra_precision) = a.mul(b);
+    fn execute_mul(&mut self, addresses: [usize; 4], exact: bool) -> Result<(), EdvacError> {
+        let a = self.get(addresses[0])?;
+        let b = self.get(addresses[1])?;
+        let kind = if exact {
+            OrderKind::MulExact
+        } else {
+            OrderKind::Mul
+        };
+        let (rounded, extra_precision) =
+            self.checked(kind, self.arithmetic_backend.mul(a, b), || {
+                arithmetic::mul(a, b)
+            })?;
 
         let dest = addresses[2];
-        self.set(dest, rounded);
+        self.set(dest, rounded)?;
 
         if exact {
-            self.set((dest + 1) & ADDRESS_MASK as usize, extra_precision);
+            self.set((dest + 1) & ADDRESS_MASK as usize, extra_precision)?;
         }
 
         self.state.initial_address_register = addresses[3];
+
+        Ok(())
     }
 
-    fn execute_div(&mut self, addresses: [usize; 4], exact: bool) {
-        let a = self.get(addresses[0]);
-        let b = self.get(addresses[1]);
-        let (rounded, extra_precision, overflow) = a.overflowing_div(b);
+    fn execute_div(&mut self, addresses: [usize; 4], exact: bool) -> Result<(), EdvacError> {
+        let a = self.get(addresses[0])?;
+        let b = self.get(addresses[1])?;
+        let kind = if exact {
+            OrderKind::DivExact
+        } else {
+            OrderKind::Div
+        };
+        let (rounded, extra_precision, overflow) =
+            self.checked(kind, self.arithmetic_backend.overflowing_div(a, b), || {
+                arithmetic::overflowing_div(a, b)
+            })?;
 
         let dest = addresses[2];
-        self.set(dest, rounded);
+        self.set(dest, rounded)?;
 
         if exact {
-            self.set((dest + 1) & ADDRESS_MASK as usize, extra_precision);
+            self.set((dest + 1) & ADDRESS_MASK as usize, extra_precision)?;
         }
 
         // to-do: Note about rounded division on FuncDesc 4-31
 
         if overflow {
-            self.handle_overflow(true, addresses[3]);
+            self.handle_overflow(true, addresses[3])
         } else {
             self.state.initial_address_register = addresses[3];
+
+            Ok(())
         }
     }
 
     // This is for executing the order `Halt`; `Edvac::halt` is for whenever the
     // machine needs to stop.
-    fn execute_halt(&mut self, addresses: [usize; 4]) {
+    fn execute_halt(&mut self, addresses: [usize; 4]) -> Result<(), EdvacError> {
         self.halt(addresses[3]);
+
+        Ok(())
     }
 
-    /// Decodes and executes the *provided* order, returning the next order that
-    /// is along the execution path, or None.
-    pub fn execute_once(&mut self, order: &Order) {
+    /// Decodes and executes the *provided* order, returning any fault it raises.
+    /// It is the operating mode running the order that puts the machine into
+    /// [`crate::EdvacStatus::Faulted`].
+    pub fn execute_once(&mut self, order: &Order) -> Result<(), EdvacError> {
         debug!(
             "Execute: {:?} {:0>4o} {:0>4o} {:0>4o} {:0>4o}",
             order.kind,
@@ -382,9 +624,12 @@ impl Edvac {
             order.addresses[3],
         );
 
+        let address = self.state.initial_address_register;
         let addresses = order.addresses;
+        let status = self.status;
+        let executing = std::mem::replace(&mut self.executing, true);
 
-        match order.kind {
+        let result = match order.kind {
             OrderKind::Compare => self.execute_compare(addresses),
             OrderKind::ManualRead => self.execute_manual_read(addresses),
             OrderKind::Add => self.execute_add(addresses),
@@ -397,7 +642,27 @@ impl Edvac {
             OrderKind::DivExact => self.execute_div(addresses, true),
             OrderKind::Halt => self.execute_halt(addresses),
 
-            OrderKind::Unused => self.halt(addresses[3]),
+            OrderKind::Unused => {
+                self.halt(addresses[3]);
+
+                Ok(())
+            }
+        };
+        self.executing = executing;
+        result?;
+
+        self.notify(EdvacEvent::OrderExecuted {
+            address,
+            order: *order,
+        });
+
+        // an order halting the machine is reported after the order itself
+        if let EdvacStatus::Halted { resume_addr } = self.status {
+            if !executing && self.status != status {
+                self.notify(EdvacEvent::Halted { resume_addr });
+            }
         }
+
+        Ok(())
     }
 }
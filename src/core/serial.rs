@@ -0,0 +1,262 @@
+//! # Bit-serial arithmetic
+//! The EDVAC's arithmetic unit took its words the way the delay lines gave them
+//! up, one bit per pulse time and least significant bit first, through a single
+//! adder with a flip-flop holding the carry over to the next pulse. The
+//! functions here work the same way on the sign and magnitude of each word,
+//! and can stand in for [`Word`]'s own arithmetic; see
+//! [`crate::arithmetic::ArithmeticBackend`].
+//!
+//! Signs are handled apart from the adder, reading the sign of a zero as +
+//! whatever it is: their product is the sign of a product or quotient, and
+//! adding magnitudes of unlike sign subtracts. The
+//! adder subtracts by adding the ones' complement, bringing the carry out of
+//! the top place back around into the bottom on another pass (an end-around
+//! carry). This is where the odd results of the machine come from, rather than
+//! being special cases:
+//! - A sum too big for a word carries out of the top like any other, so the
+//!   carry brought back around leaves the magnitude one more than what
+//!   overflowed. The sign is that of the operands.
+//! - A difference of zero comes out of the adder as all ones, or -0, and like
+//!   everywhere else the machine encodes zero only as +0 (prelim. report pg.
+//!   37).
+//! - A quotient is built one bit at a time by trying to subtract the divisor
+//!   from the remainder. A dividend at least as big as the divisor, including
+//!   any dividend over a divisor of zero, gets through the first trial, and
+//!   from then on the remainder is always big enough to get through every
+//!   trial after it: division overflow, with every bit of the quotient a one.
+
+use crate::word::{Word, BIT_WIDTH, U43_MAX};
+
+/// Bits in the magnitude of a word.
+const MAGNITUDE_WIDTH: usize = BIT_WIDTH - 1;
+
+/// Adds the low `width` bits of `a` and `b` and `carry` a bit at a time, giving
+/// the sum and the carry out of the top place.
+fn add(a: u64, b: u64, mut carry: bool, width: usize) -> (u64, bool) {
+    let mut sum = 0;
+
+    for place in 0..width {
+        let a = (a >> place) & 0b1 == 0b1;
+        let b = (b >> place) & 0b1 == 0b1;
+
+        sum |= u64::from(a ^ b ^ carry) << place;
+        carry = (a && b) || (carry && (a ^ b));
+    }
+
+    (sum, carry)
+}
+
+/// Adds two magnitudes with an end-around carry, giving the sum and whether
+/// anything carried out of the top place.
+fn end_around_add(a: u64, b: u64) -> (u64, bool) {
+    let (sum, carry) = add(a, b, false, MAGNITUDE_WIDTH);
+    let (sum, _) = add(sum, 0, carry, MAGNITUDE_WIDTH);
+
+    (sum, carry)
+}
+
+/// -0 is read as 0.
+fn sign(word: Word) -> bool {
+    word.get_bits() & 0b1 == 0b1 && magnitude(word) != 0
+}
+
+fn magnitude(word: Word) -> u64 {
+    word.get_bits() >> 1
+}
+
+/// Zero is only encoded as +0.
+fn encode(negative: bool, magnitude: u64) -> Word {
+    Word::from_bits(magnitude << 1 | u64::from(negative && magnitude != 0))
+}
+
+/// Serial version of [`Word::overflowing_add`].
+#[must_use]
+pub fn overflowing_add(a: Word, b: Word) -> (Word, bool) {
+    if sign(a) == sign(b) {
+        let (sum, carry) = end_around_add(magnitude(a), magnitude(b));
+
+        (encode(sign(a), sum), carry)
+    } else {
+        let (sum, carry) = end_around_add(magnitude(a), !magnitude(b) & U43_MAX);
+
+        if carry {
+            (encode(sign(a), sum), false)
+        } else {
+            // b was the bigger, and the sum is the complement of the difference
+            (encode(sign(b), !sum & U43_MAX), false)
+        }
+    }
+}
+
+/// Serial version of [`Word::overflowing_sub`]; adds the subtrahend with its
+/// sign reversed.
+#[must_use]
+pub fn overflowing_sub(a: Word, b: Word) -> (Word, bool) {
+    overflowing_add(a, Word::from_bits(b.get_bits() ^ 0b1))
+}
+
+/// Serial version of [`Word::mul`]. The product builds up in two words: for
+/// every bit of the multiplier, lowest first, the multiplicand is added into the
+/// upper word if the bit is a one, and then both words are shifted one place
+/// down, the bottom bit of the upper word going into the top of the lower one.
+#[must_use]
+pub fn mul(a: Word, b: Word) -> (Word, Word) {
+    let multiplicand = magnitude(a);
+    let multiplier = magnitude(b);
+
+    let (mut upper, mut lower) = (0, 0);
+    for place in 0..MAGNITUDE_WIDTH {
+        let carry = if (multiplier >> place) & 0b1 == 0b1 {
+            let (sum, carry) = add(upper, multiplicand, false, MAGNITUDE_WIDTH);
+            upper = sum;
+            carry
+        } else {
+            false
+        };
+
+        lower = (lower >> 1) | (upper & 0b1) << (MAGNITUDE_WIDTH - 1);
+        upper = (upper >> 1) | u64::from(carry) << (MAGNITUDE_WIDTH - 1);
+    }
+
+    let negative = sign(a) != sign(b);
+
+    (encode(negative, upper), encode(negative, lower))
+}
+
+/// Serial version of [`Word::overflowing_div`]. The remainder is held in a
+/// register one place wider than a magnitude, and anything doubled out of the
+/// top of it is remembered, since a remainder that big gets through every
+/// trial subtraction from then on.
+#[must_use]
+pub fn overflowing_div(a: Word, b: Word) -> (Word, Word, bool) {
+    const REMAINDER_WIDTH: usize = MAGNITUDE_WIDTH + 1;
+    let divisor_complement = !magnitude(b) & ((1 << REMAINDER_WIDTH) - 1);
+
+    let mut remainder = magnitude(a);
+    let mut excess = false;
+    let trial = |remainder: &mut u64, excess: bool| {
+        // subtracting by adding the two's complement, so a carry out means
+        // nothing had to be borrowed
+        let (difference, carry) = add(*remainder, divisor_complement, true, REMAINDER_WIDTH);
+        let fits = excess || carry;
+        if fits {
+            *remainder = difference;
+        }
+
+        fits
+    };
+
+    // The place in front of the point, which a quotient has no room for. This
+    // trial only tests, leaving the remainder as it was.
+    let mut tested = remainder;
+    let overflowed = trial(&mut tested, excess);
+
+    let mut quotient = 0_u128;
+    for _ in 0..2 * MAGNITUDE_WIDTH {
+        excess |= (remainder >> (REMAINDER_WIDTH - 1)) & 0b1 == 0b1;
+        remainder = (remainder << 1) & ((1 << REMAINDER_WIDTH) - 1);
+
+        quotient = quotient << 1 | u128::from(trial(&mut remainder, excess));
+    }
+
+    let upper = (quotient >> MAGNITUDE_WIDTH) as u64;
+    let lower = quotient as u64 & U43_MAX;
+    let negative = sign(a) != sign(b);
+
+    (encode(negative, upper), encode(negative, lower), overflowed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::arithmetic::ArithmeticBackend;
+    use crate::Edvac;
+
+    #[test]
+    fn adder() {
+        assert_eq!(add(0b0110, 0b0011, false, 4), (0b1001, false));
+        assert_eq!(add(0b1111, 0b0000, true, 4), (0b0000, true));
+        assert_eq!(end_around_add(U43_MAX, 1), (1, true));
+        assert_eq!(end_around_add(5, !5 & U43_MAX), (U43_MAX, false));
+    }
+
+    #[test]
+    fn edge_cases() {
+        let max: Word = (U43_MAX as i64).try_into().unwrap();
+        let one: Word = 1.try_into().unwrap();
+        let negative_zero = Word::from_bits(0b1);
+        let zero = Word::from_bits(0);
+
+        // overflow keeps the sign, one past what overflowed
+        assert_eq!(overflowing_add(max, one), (one, true));
+        assert_eq!(overflowing_add(-max, -one), (-one, true));
+        assert_eq!(overflowing_sub(max, -max), (max, true));
+
+        // ±0 sums come out as +0
+        assert_eq!(overflowing_add(one, -one), (zero, false));
+        assert_eq!(overflowing_add(negative_zero, negative_zero), (zero, false));
+        assert_eq!(overflowing_sub(negative_zero, zero), (zero, false));
+
+        // halves of a product that are zero are +0 too
+        assert_eq!(mul(-one, one), (zero, -one));
+        assert_eq!(mul(negative_zero, -one), (zero, zero));
+
+        // dividing by ±0 fills the quotient with ones
+        let filled = Word::from_bits(U43_MAX << 1);
+        assert_eq!(overflowing_div(one, zero), (filled, filled, true));
+        assert_eq!(overflowing_div(one, negative_zero), (filled, filled, true));
+        assert_eq!(overflowing_div(-one, zero), (-filled, -filled, true));
+        assert_eq!(overflowing_div(-max, -max), (filled, filled, true));
+        assert_eq!(overflowing_div(zero, max), (zero, zero, false));
+    }
+
+    #[test]
+    fn agrees_with_word() {
+        let mut words: Vec<Word> = [0, 1, -1, 3, -4_294_967_296, 65535, U43_MAX as i64]
+            .iter()
+            .map(|&n| n.try_into().unwrap())
+            .collect();
+        words.push(Word::from_bits(0b1));
+
+        for &a in &words {
+            for &b in &words {
+                assert_eq!(a.overflowing_add(b), overflowing_add(a, b), "{a:?} + {b:?}");
+                assert_eq!(a.overflowing_sub(b), overflowing_sub(a, b), "{a:?} - {b:?}");
+                assert_eq!(a.mul(b), mul(a, b), "{a:?} * {b:?}");
+                assert_eq!(a.overflowing_div(b), overflowing_div(a, b), "{a:?} / {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn backend() {
+        let mut computer = Edvac {
+            arithmetic_backend: ArithmeticBackend::Serial,
+            arithmetic_check: true,
+            ..Default::default()
+        };
+        computer
+            .high_speed_memory
+            .load(vec![
+                (0o0000, inst!(m 0o0100 0o0101 0o0102 0o0001)),
+                (0o0001, inst!(d 0o0103 0o0104 0o0105 0o0002)),
+                (0o0002, inst!(H 0o0000 0o0000 0o0000 0o0000)),
+                (0o0100, (-12345).try_into().unwrap()),
+                (0o0101, 678.try_into().unwrap()),
+                (0o0104, 10_000_000.try_into().unwrap()),
+            ])
+            .unwrap();
+
+        computer.initiate_pressed();
+        computer.continue_to_completion().unwrap();
+
+        let product = computer.get(0o0103).unwrap();
+        assert_eq!(computer.get(0o0102).unwrap().get_bits(), 0);
+        assert_eq!(i64::from(product), -12345 * 678);
+
+        let (upper, lower, _) = product.overflowing_div(computer.get(0o0104).unwrap());
+        assert_eq!(computer.get(0o0105).unwrap(), upper);
+        assert_eq!(computer.get(0o0106).unwrap(), lower);
+    }
+}
@@ -0,0 +1,887 @@
+//! # Snapshots
+//! A snapshot holds everything needed to pick a run back up exactly where it
+//! was left: the console state (including an order caught between its cycle and
+//! execute phases), high-speed memory, the status of the machine, all three
+//! wires along with the position of each, the simulated clock and wire
+//! transports, whatever has been typed, the drum and card unit if there are any,
+//! and which arithmetic is used and whether it's checked.
+//!
+//! The format is a simple binary one. It starts with [`MAGIC`] and the format
+//! [`VERSION`], and every integer after that is little-endian. Addresses are
+//! stored as `u64`s, words as their raw bits and each wire as it would be saved
+//! by [`Wire::save`].
+
+use std::{
+    fmt,
+    io::{self, Read, Write},
+};
+
+use crate::{
+    arithmetic::ArithmeticBackend,
+    cards::{Card, CardDeck, CardFormat, CardUnit, COLUMNS},
+    drum::{Drum, DRUM_SIZE},
+    error::EdvacError,
+    high_speed_memory::{HighSpeedMemory, ADDRESS_MASK},
+    operating_console::{ExcessCapacityAction, MemoryMode, OperatingMode, State},
+    orders::{CycledOrder, Order, OrderKind, OrderSource},
+    timing::SimulatedClock,
+    transport::{TransportSpeeds, TransportUsage, WireEndAction, WireTransport},
+    typewriter::{Typewriter, TypewriterMode},
+    wire::{Wire, WireSpool},
+    word::{Word, BIT_WIDTH},
+    Edvac, EdvacStatus,
+};
+
+/// Every snapshot starts with these bytes.
+pub const MAGIC: [u8; 8] = *b"EDVACSNP";
+
+/// Bumped whenever the layout of a snapshot changes.
+pub const VERSION: u16 = 1;
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    /// The data doesn't start with [`MAGIC`].
+    NotASnapshot,
+    /// The snapshot was written by a different version of the format.
+    UnsupportedVersion {
+        version: u16,
+    },
+    /// The snapshot is well-formed but holds something the machine can't be in.
+    Corrupt {
+        field: &'static str,
+    },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::Io(error) => write!(f, "{}", error),
+            SnapshotError::NotASnapshot => write!(f, "not an EDVAC snapshot"),
+            SnapshotError::UnsupportedVersion { version } => write!(
+                f,
+                "snapshot format version {} is not supported (expected {})",
+                version, VERSION
+            ),
+            SnapshotError::Corrupt { field } => write!(f, "snapshot has a bad {}", field),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(error: io::Error) -> Self {
+        SnapshotError::Io(error)
+    }
+}
+
+struct Encoder<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> Encoder<W> {
+    fn u8(&mut self, value: u8) -> io::Result<()> {
+        self.writer.write_all(&[value])
+    }
+
+    fn u16(&mut self, value: u16) -> io::Result<()> {
+        self.writer.write_all(&value.to_le_bytes())
+    }
+
+    fn u64(&mut self, value: u64) -> io::Result<()> {
+        self.writer.write_all(&value.to_le_bytes())
+    }
+
+    fn address(&mut self, address: usize) -> io::Result<()> {
+        self.u64(address as u64)
+    }
+
+    fn word(&mut self, word: Word) -> io::Result<()> {
+        self.u64(word.get_bits())
+    }
+
+    fn order(&mut self, order: &Order) -> io::Result<()> {
+        self.u8(order.kind.to_bits() as u8)?;
+
+        for &address in &order.addresses {
+            self.address(address)?;
+        }
+
+        Ok(())
+    }
+
+    fn error(&mut self, error: EdvacError) -> io::Result<()> {
+        match error {
+            EdvacError::AddressOutOfRange { address } => {
+                self.u8(0)?;
+                self.address(address)
+            }
+            EdvacError::InvalidWireSpool { spool_id } => {
+                self.u8(1)?;
+                self.u64(spool_id)
+            }
+            EdvacError::ReadAddressFromWire { spool } => {
+                self.u8(2)?;
+                self.spool(spool)
+            }
+            EdvacError::EndOfWire { spool } => {
+                self.u8(3)?;
+                self.spool(spool)
+            }
+            EdvacError::BeginningOfWire { spool } => {
+                self.u8(4)?;
+                self.spool(spool)
+            }
+            EdvacError::DrumAddressOutOfRange { address } => {
+                self.u8(5)?;
+                self.address(address)
+            }
+            EdvacError::CardHopperEmpty { spool } => {
+                self.u8(6)?;
+                self.spool(spool)
+            }
+            EdvacError::UnreadableCard { spool } => {
+                self.u8(7)?;
+                self.spool(spool)
+            }
+            EdvacError::ArithmeticCheckFailure { order } => {
+                self.u8(8)?;
+                self.u8(order.to_bits() as u8)
+            }
+        }
+    }
+
+    fn spool(&mut self, spool: WireSpool) -> io::Result<()> {
+        self.u8(match spool {
+            WireSpool::Zero => 0,
+            WireSpool::One => 1,
+            WireSpool::Two => 2,
+            WireSpool::Three => 3,
+        })
+    }
+
+    fn status(&mut self, status: EdvacStatus) -> io::Result<()> {
+        match status {
+            EdvacStatus::Running => self.u8(0),
+            EdvacStatus::Halted { resume_addr } => {
+                self.u8(1)?;
+                self.address(resume_addr)
+            }
+            EdvacStatus::Faulted { resume_addr, error } => {
+                self.u8(2)?;
+                self.address(resume_addr)?;
+                self.error(error)
+            }
+        }
+    }
+
+    fn excess_capacity_action(&mut self, action: ExcessCapacityAction) -> io::Result<()> {
+        self.u8(match action {
+            ExcessCapacityAction::Halt => 0,
+            ExcessCapacityAction::Ignore => 1,
+            ExcessCapacityAction::ExecuteSpecial => 2,
+            ExcessCapacityAction::ExecuteAddressB => 3,
+        })
+    }
+
+    fn state(&mut self, state: &State) -> io::Result<()> {
+        self.address(state.initial_address_register)?;
+
+        match state.order_register {
+            None => self.u8(0)?,
+            Some(CycledOrder { order, source }) => {
+                self.u8(match source {
+                    OrderSource::Memory => 1,
+                    OrderSource::SpecialOrderSwitches => 2,
+                })?;
+                self.order(&order)?;
+            }
+        }
+
+        self.u8(match state.operating_mode {
+            OperatingMode::SpecialOneCycle => 0,
+            OperatingMode::SpecialOneExecute => 1,
+            OperatingMode::SpecialOneOrder => 2,
+            OperatingMode::NormalToCompletion => 3,
+            OperatingMode::NormalToAddressA => 4,
+            OperatingMode::NormalOneCycle => 5,
+            OperatingMode::NormalOneExecute => 6,
+            OperatingMode::NormalOneOrder => 7,
+        })?;
+
+        self.excess_capacity_action(state.excess_capacity_action_add)?;
+        self.excess_capacity_action(state.excess_capacity_action_div)?;
+        self.u8(match state.memory_mode {
+            MemoryMode::L0 => 0,
+            MemoryMode::LR => 1,
+            MemoryMode::R1 => 2,
+        })?;
+
+        self.word(state.auxiliary_input_switches)?;
+        self.word(state.special_order_switches)?;
+        self.address(state.address_a_switches)?;
+        self.address(state.address_b_switches)
+    }
+
+    fn wire(&mut self, wire: &Wire) -> io::Result<()> {
+        wire.save(&mut self.writer)
+    }
+
+    fn typewriter(&mut self, typewriter: &Typewriter) -> io::Result<()> {
+        self.u8(match typewriter.mode {
+            TypewriterMode::Octal => 0,
+            TypewriterMode::DecimalFraction => 1,
+        })?;
+        self.u64(typewriter.output().len() as u64)?;
+        self.writer.write_all(typewriter.output().as_bytes())
+    }
+
+    fn drum(&mut self, drum: Option<&Drum>) -> io::Result<()> {
+        match drum {
+            None => self.u8(0),
+            Some(drum) => {
+                self.u8(1)?;
+                self.u64(drum.word_time)?;
+
+                for &word in drum.dump() {
+                    self.word(word)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    fn deck(&mut self, deck: &CardDeck) -> io::Result<()> {
+        self.u64(deck.cards().len() as u64)?;
+
+        for card in deck.cards() {
+            for &column in card.columns() {
+                self.u16(column)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn card_unit(&mut self, unit: Option<&CardUnit>) -> io::Result<()> {
+        match unit {
+            None => self.u8(0),
+            Some(unit) => {
+                self.u8(1)?;
+                self.spool(unit.spool)?;
+                self.u8(match unit.format {
+                    CardFormat::Octal => 0,
+                    CardFormat::ColumnBinary => 1,
+                })?;
+                self.deck(unit.hopper_deck())?;
+                self.u64(unit.cards_read() as u64)?;
+                self.deck(unit.punched())
+            }
+        }
+    }
+
+    fn transport(&mut self, transport: &WireTransport) -> io::Result<()> {
+        let (speeds, usage) = (transport.speeds, transport.usage());
+
+        self.u64(speeds.bit_time)?;
+        self.u64(speeds.rewind_bit_time)?;
+        self.u64(speeds.reversal_time)?;
+        self.u64(usage.reading)?;
+        self.u64(usage.recording)?;
+        self.u64(usage.translating)?;
+        self.u64(usage.rewinding)?;
+        self.u8(transport.backward() as u8)?;
+        self.u8(match transport.end_action {
+            WireEndAction::Halt => 0,
+            WireEndAction::Stop => 1,
+        })
+    }
+}
+
+struct Decoder<R: Read> {
+    reader: R,
+}
+
+impl<R: Read> Decoder<R> {
+    fn u8(&mut self) -> io::Result<u8> {
+        let mut bytes = [0; 1];
+        self.reader.read_exact(&mut bytes)?;
+
+        Ok(bytes[0])
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        let mut bytes = [0; 2];
+        self.reader.read_exact(&mut bytes)?;
+
+        Ok(u16::from_le_bytes(bytes))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        let mut bytes = [0; 8];
+        self.reader.read_exact(&mut bytes)?;
+
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn address(&mut self) -> Result<usize, SnapshotError> {
+        usize::try_from(self.u64()?).map_err(|_| SnapshotError::Corrupt { field: "address" })
+    }
+
+    /// An address that has to fit in the 10 bits of an order's address field.
+    fn order_address(&mut self) -> Result<usize, SnapshotError> {
+        match self.address()? {
+            address if address as u64 <= ADDRESS_MASK => Ok(address),
+            _ => Err(SnapshotError::Corrupt { field: "address" }),
+        }
+    }
+
+    fn word(&mut self) -> Result<Word, SnapshotError> {
+        match self.u64()? {
+            bits if bits >> BIT_WIDTH == 0 => Ok(Word::from_bits(bits)),
+            _ => Err(SnapshotError::Corrupt { field: "word" }),
+        }
+    }
+
+    fn order_kind(&mut self) -> Result<OrderKind, SnapshotError> {
+        match self.u8()? {
+            bits if bits <= 0b1111 => Ok(OrderKind::from(Word::from_bits(u64::from(bits)))),
+            _ => Err(SnapshotError::Corrupt { field: "order" }),
+        }
+    }
+
+    fn order(&mut self) -> Result<Order, SnapshotError> {
+        let kind = self.order_kind()?;
+
+        let mut addresses = [0; 4];
+        for address in &mut addresses {
+            *address = self.order_address()?;
+        }
+
+        Ok(Order { kind, addresses })
+    }
+
+    fn error(&mut self) -> Result<EdvacError, SnapshotError> {
+        Ok(match self.u8()? {
+            0 => EdvacError::AddressOutOfRange {
+                address: self.address()?,
+            },
+            1 => EdvacError::InvalidWireSpool {
+                spool_id: self.u64()?,
+            },
+            2 => EdvacError::ReadAddressFromWire {
+                spool: self.spool()?,
+            },
+            3 => EdvacError::EndOfWire {
+                spool: self.spool()?,
+            },
+            4 => EdvacError::BeginningOfWire {
+                spool: self.spool()?,
+            },
+            5 => EdvacError::DrumAddressOutOfRange {
+                address: self.address()?,
+            },
+            6 => EdvacError::CardHopperEmpty {
+                spool: self.spool()?,
+            },
+            7 => EdvacError::UnreadableCard {
+                spool: self.spool()?,
+            },
+            8 => EdvacError::ArithmeticCheckFailure {
+                order: self.order_kind()?,
+            },
+            _ => return Err(SnapshotError::Corrupt { field: "fault" }),
+        })
+    }
+
+    fn spool(&mut self) -> Result<WireSpool, SnapshotError> {
+        WireSpool::try_from(u64::from(self.u8()?)).map_err(|()| SnapshotError::Corrupt {
+            field: "wire spool",
+        })
+    }
+
+    fn status(&mut self) -> Result<EdvacStatus, SnapshotError> {
+        Ok(match self.u8()? {
+            0 => EdvacStatus::Running,
+            1 => EdvacStatus::Halted {
+                resume_addr: self.address()?,
+            },
+            2 => EdvacStatus::Faulted {
+                resume_addr: self.address()?,
+                error: self.error()?,
+            },
+            _ => return Err(SnapshotError::Corrupt { field: "status" }),
+        })
+    }
+
+    fn excess_capacity_action(&mut self) -> Result<ExcessCapacityAction, SnapshotError> {
+        Ok(match self.u8()? {
+            0 => ExcessCapacityAction::Halt,
+            1 => ExcessCapacityAction::Ignore,
+            2 => ExcessCapacityAction::ExecuteSpecial,
+            3 => ExcessCapacityAction::ExecuteAddressB,
+            _ => {
+                return Err(SnapshotError::Corrupt {
+                    field: "excess capacity action",
+                })
+            }
+        })
+    }
+
+    fn state(&mut self) -> Result<State, SnapshotError> {
+        let initial_address_register = self.address()?;
+
+        let order_register = match self.u8()? {
+            0 => None,
+            source @ (1 | 2) => Some(CycledOrder {
+                order: self.order()?,
+                source: if source == 1 {
+                    OrderSource::Memory
+                } else {
+                    OrderSource::SpecialOrderSwitches
+                },
+            }),
+            _ => {
+                return Err(SnapshotError::Corrupt {
+                    field: "order register",
+                })
+            }
+        };
+
+        let operating_mode = match self.u8()? {
+            0 => OperatingMode::SpecialOneCycle,
+            1 => OperatingMode::SpecialOneExecute,
+            2 => OperatingMode::SpecialOneOrder,
+            3 => OperatingMode::NormalToCompletion,
+            4 => OperatingMode::NormalToAddressA,
+            5 => OperatingMode::NormalOneCycle,
+            6 => OperatingMode::NormalOneExecute,
+            7 => OperatingMode::NormalOneOrder,
+            _ => {
+                return Err(SnapshotError::Corrupt {
+                    field: "operating mode",
+                })
+            }
+        };
+
+        let excess_capacity_action_add = self.excess_capacity_action()?;
+        let excess_capacity_action_div = self.excess_capacity_action()?;
+        let memory_mode = match self.u8()? {
+            0 => MemoryMode::L0,
+            1 => MemoryMode::LR,
+            2 => MemoryMode::R1,
+            _ => {
+                return Err(SnapshotError::Corrupt {
+                    field: "memory mode",
+                })
+            }
+        };
+
+        Ok(State {
+            initial_address_register,
+            order_register,
+            operating_mode,
+            excess_capacity_action_add,
+            excess_capacity_action_div,
+            memory_mode,
+            auxiliary_input_switches: self.word()?,
+            special_order_switches: self.word()?,
+            address_a_switches: self.order_address()?,
+            address_b_switches: self.order_address()?,
+        })
+    }
+
+    fn wire(&mut self) -> Result<Wire, SnapshotError> {
+        Wire::load(&mut self.reader).map_err(|error| match error.kind() {
+            io::ErrorKind::InvalidData => SnapshotError::Corrupt { field: "wire" },
+            _ => SnapshotError::Io(error),
+        })
+    }
+
+    fn typewriter(&mut self) -> Result<Typewriter, SnapshotError> {
+        let mode = match self.u8()? {
+            0 => TypewriterMode::Octal,
+            1 => TypewriterMode::DecimalFraction,
+            _ => {
+                return Err(SnapshotError::Corrupt {
+                    field: "typewriter mode",
+                })
+            }
+        };
+
+        let length = self.u64()?;
+        let mut output = Vec::new();
+        (&mut self.reader).take(length).read_to_end(&mut output)?;
+        if output.len() as u64 != length {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+
+        let output = String::from_utf8(output).map_err(|_| SnapshotError::Corrupt {
+            field: "typewriter output",
+        })?;
+
+        Ok(Typewriter::with_output(mode, output))
+    }
+
+    fn drum(&mut self) -> Result<Option<Drum>, SnapshotError> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => {
+                let word_time = self.u64()?;
+                let mut words = Vec::with_capacity(DRUM_SIZE);
+                for address in 0..DRUM_SIZE {
+                    words.push((address, self.word()?));
+                }
+
+                let mut drum = Drum::default();
+                drum.word_time = word_time;
+                drum.load(words).unwrap();
+
+                Ok(Some(drum))
+            }
+            _ => Err(SnapshotError::Corrupt { field: "drum" }),
+        }
+    }
+
+    fn deck(&mut self) -> Result<CardDeck, SnapshotError> {
+        let length = self.u64()?;
+
+        let mut cards = Vec::new();
+        for _ in 0..length {
+            let mut columns = [0; COLUMNS];
+            for column in &mut columns {
+                *column = match self.u16()? {
+                    column if column >> 12 == 0 => column,
+                    _ => return Err(SnapshotError::Corrupt { field: "card" }),
+                };
+            }
+
+            cards.push(Card::new(columns));
+        }
+
+        Ok(CardDeck::new(cards))
+    }
+
+    fn card_unit(&mut self) -> Result<Option<CardUnit>, SnapshotError> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => {
+                let spool = self.spool()?;
+                let format = match self.u8()? {
+                    0 => CardFormat::Octal,
+                    1 => CardFormat::ColumnBinary,
+                    _ => {
+                        return Err(SnapshotError::Corrupt {
+                            field: "card format",
+                        })
+                    }
+                };
+                let hopper = self.deck()?;
+                let next_card = match self.address()? {
+                    next_card if next_card <= hopper.cards().len() => next_card,
+                    _ => {
+                        return Err(SnapshotError::Corrupt {
+                            field: "card hopper",
+                        })
+                    }
+                };
+                let stacker = self.deck()?;
+
+                Ok(Some(CardUnit::with_decks(
+                    spool, format, hopper, next_card, stacker,
+                )))
+            }
+            _ => Err(SnapshotError::Corrupt { field: "card unit" }),
+        }
+    }
+
+    fn transport(&mut self) -> Result<WireTransport, SnapshotError> {
+        let speeds = TransportSpeeds {
+            bit_time: self.u64()?,
+            rewind_bit_time: self.u64()?,
+            reversal_time: self.u64()?,
+        };
+        let usage = TransportUsage {
+            reading: self.u64()?,
+            recording: self.u64()?,
+            translating: self.u64()?,
+            rewinding: self.u64()?,
+        };
+        let backward = match self.u8()? {
+            0 => false,
+            1 => true,
+            _ => {
+                return Err(SnapshotError::Corrupt {
+                    field: "wire direction",
+                })
+            }
+        };
+
+        let end_action = match self.u8()? {
+            0 => WireEndAction::Halt,
+            1 => WireEndAction::Stop,
+            _ => {
+                return Err(SnapshotError::Corrupt {
+                    field: "wire end action",
+                })
+            }
+        };
+
+        let mut transport = WireTransport::with_parts(speeds, usage, backward);
+        transport.end_action = end_action;
+
+        Ok(transport)
+    }
+}
+
+/// # Snapshots
+impl Edvac {
+    /// Writes a snapshot of the whole machine to `writer`. Writing to a file is
+    /// much faster through a [`std::io::BufWriter`].
+    pub fn save_snapshot(&self, writer: impl Write) -> Result<(), SnapshotError> {
+        let mut encoder = Encoder { writer };
+
+        encoder.writer.write_all(&MAGIC)?;
+        encoder.u16(VERSION)?;
+
+        encoder.state(&self.state)?;
+        encoder.status(self.status)?;
+        encoder.u64(self.clock.pulse_times())?;
+        for transport in &self.wire_transports {
+            encoder.transport(transport)?;
+        }
+
+        for word in self.high_speed_memory.dump() {
+            encoder.word(word)?;
+        }
+
+        for wire in &self.low_speed_memory {
+            encoder.wire(wire)?;
+        }
+
+        encoder.typewriter(&self.typewriter)?;
+        encoder.drum(self.drum.as_ref())?;
+        encoder.card_unit(self.card_unit.as_ref())?;
+        encoder.u8(match self.arithmetic_backend {
+            ArithmeticBackend::Native => 0,
+            ArithmeticBackend::Serial => 1,
+        })?;
+        encoder.u8(self.arithmetic_check as u8)?;
+
+        encoder.writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Reads a snapshot written by [`Edvac::save_snapshot`] into a new machine.
+    pub fn load_snapshot(reader: impl Read) -> Result<Edvac, SnapshotError> {
+        let mut computer = Edvac::default();
+        computer.restore_snapshot(reader)?;
+
+        Ok(computer)
+    }
+
+    /// Puts this machine into the state recorded in a snapshot. If the snapshot
+    /// can't be read, the machine is left untouched.
+    pub fn restore_snapshot(&mut self, reader: impl Read) -> Result<(), SnapshotError> {
+        let mut decoder = Decoder { reader };
+
+        let mut magic = [0; MAGIC.len()];
+        decoder
+            .reader
+            .read_exact(&mut magic)
+            .map_err(|_| SnapshotError::NotASnapshot)?;
+        if magic != MAGIC {
+            return Err(SnapshotError::NotASnapshot);
+        }
+
+        let version = decoder.u16()?;
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion { version });
+        }
+
+        let state = decoder.state()?;
+        let status = decoder.status()?;
+
+        let mut clock = SimulatedClock::default();
+        clock.advance(decoder.u64()?);
+        let wire_transports = [
+            decoder.transport()?,
+            decoder.transport()?,
+            decoder.transport()?,
+        ];
+
+        let mut words = Vec::with_capacity(1024);
+        for address in 0..1024 {
+            words.push((address, decoder.word()?));
+        }
+        let mut high_speed_memory = HighSpeedMemory::default();
+        high_speed_memory.load(words).unwrap();
+
+        let low_speed_memory = [decoder.wire()?, decoder.wire()?, decoder.wire()?];
+        let typewriter = decoder.typewriter()?;
+        let drum = decoder.drum()?;
+        let card_unit = decoder.card_unit()?;
+        let arithmetic_backend = match decoder.u8()? {
+            0 => ArithmeticBackend::Native,
+            1 => ArithmeticBackend::Serial,
+            _ => {
+                return Err(SnapshotError::Corrupt {
+                    field: "arithmetic backend",
+                })
+            }
+        };
+        let arithmetic_check = match decoder.u8()? {
+            0 => false,
+            1 => true,
+            _ => {
+                return Err(SnapshotError::Corrupt {
+                    field: "arithmetic check",
+                })
+            }
+        };
+
+        self.state = state;
+        self.status = status;
+        self.clock = clock;
+        self.wire_transports = wire_transports;
+        self.high_speed_memory = high_speed_memory;
+        self.low_speed_memory = low_speed_memory;
+        self.typewriter = typewriter;
+        self.drum = drum;
+        self.card_unit = card_unit;
+        self.arithmetic_backend = arithmetic_backend;
+        self.arithmetic_check = arithmetic_check;
+        self.journal.clear();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_programs::dec_to_bin;
+
+    fn assert_same(a: &Edvac, b: &Edvac) {
+        assert_eq!(a.status, b.status);
+        assert_eq!(a.clock, b.clock);
+        assert_eq!(a.wire_transports, b.wire_transports);
+        assert_eq!(a.typewriter.mode, b.typewriter.mode);
+        assert_eq!(a.typewriter.output(), b.typewriter.output());
+        assert_eq!(a.state, b.state);
+        assert_eq!(a.high_speed_memory.dump(), b.high_speed_memory.dump());
+        assert_eq!(a.drum, b.drum);
+        assert_eq!(a.card_unit, b.card_unit);
+        assert_eq!(a.arithmetic_backend, b.arithmetic_backend);
+        assert_eq!(a.arithmetic_check, b.arithmetic_check);
+
+        for (a, b) in a.low_speed_memory.iter().zip(&b.low_speed_memory) {
+            let (mut a_file, mut b_file) = (Vec::new(), Vec::new());
+            a.save(&mut a_file).unwrap();
+            b.save(&mut b_file).unwrap();
+
+            assert_eq!(a_file, b_file);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut computer = Edvac::default();
+        dec_to_bin::load(&mut computer);
+        let mut drum = Drum::default();
+        drum.word_time = 100;
+        drum.set(DRUM_SIZE - 1, Word::from_bits(0o17)).unwrap();
+        computer.drum = Some(drum);
+        let mut card_unit = CardUnit::new(WireSpool::Three, CardFormat::ColumnBinary);
+        card_unit.load_hopper(CardDeck::punch_words(
+            CardFormat::ColumnBinary,
+            &[Word::from_bits(0o7)],
+        ));
+        computer.card_unit = Some(card_unit);
+        computer.arithmetic_backend = ArithmeticBackend::Serial;
+        computer.arithmetic_check = true;
+
+        computer.initiate_pressed();
+        for _ in 0..20 {
+            computer.step_once().unwrap();
+        }
+        computer.one_cycle(false).unwrap();
+
+        let mut snapshot = Vec::new();
+        computer.save_snapshot(&mut snapshot).unwrap();
+
+        let mut restored = Edvac::load_snapshot(snapshot.as_slice()).unwrap();
+        assert_same(&computer, &restored);
+
+        for computer in [&mut computer, &mut restored] {
+            computer.initiate_pressed();
+            computer.continue_to_completion().unwrap();
+        }
+        assert_same(&computer, &restored);
+    }
+
+    #[test]
+    fn console_state() {
+        let computer = Edvac {
+            state: State {
+                initial_address_register: 0o1234,
+                order_register: Some(CycledOrder {
+                    order: inst!(E 0o0001 0o0002 0o0003 0o0004).into(),
+                    source: OrderSource::SpecialOrderSwitches,
+                }),
+                operating_mode: OperatingMode::NormalToAddressA,
+                excess_capacity_action_add: ExcessCapacityAction::ExecuteAddressB,
+                excess_capacity_action_div: ExcessCapacityAction::ExecuteSpecial,
+                memory_mode: MemoryMode::R1,
+                auxiliary_input_switches: Word::from_bits(0o1357),
+                special_order_switches: inst!(H 0o0000 0o0000 0o0000 0o0005),
+                address_a_switches: 0o0765,
+                address_b_switches: 0o1777,
+            },
+            ..Default::default()
+        };
+
+        let mut snapshot = Vec::new();
+        computer.save_snapshot(&mut snapshot).unwrap();
+
+        assert_same(
+            &computer,
+            &Edvac::load_snapshot(snapshot.as_slice()).unwrap(),
+        );
+    }
+
+    #[test]
+    fn bad_snapshots() {
+        let mut snapshot = Vec::new();
+        Edvac::default().save_snapshot(&mut snapshot).unwrap();
+
+        assert!(matches!(
+            Edvac::load_snapshot(&snapshot[1..]),
+            Err(SnapshotError::NotASnapshot)
+        ));
+
+        snapshot[MAGIC.len()] = 0xFF;
+        assert!(matches!(
+            Edvac::load_snapshot(snapshot.as_slice()),
+            Err(SnapshotError::UnsupportedVersion { .. })
+        ));
+
+        snapshot[MAGIC.len()] = VERSION as u8;
+        snapshot.truncate(snapshot.len() - 1);
+        assert!(matches!(
+            Edvac::load_snapshot(snapshot.as_slice()),
+            Err(SnapshotError::Io(_))
+        ));
+    }
+}
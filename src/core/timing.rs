@@ -0,0 +1,111 @@
+//! # Simulated time
+//! The high-speed memory of the EDVAC is made up of mercury delay lines, each of
+//! which holds 8 words that circulate past a single read/write point. A word is
+//! only available while it is passing that point, so the time an order takes
+//! depends heavily on *where* its operands are stored. Programmers of the day
+//! spent a great deal of effort on "optimum coding" to exploit this.
+//!
+//! Only the waits caused by the delay lines and the wires (see
+//! [`crate::transport`]) are charged to the clock; the time taken by the
+//! arithmetic circuits themselves is not modeled.
+
+use crate::high_speed_memory::WORDS_PER_TANK;
+
+/// The EDVAC's master clock ran at 1 Mc, so every pulse time is 1 μs.
+pub const PULSE_TIME_NANOS: u64 = 1000;
+
+/// Each word occupies 48 pulse times in a delay line: 44 bits of the word plus a
+/// 4 pulse-time gap between words.
+pub const WORD_TIME: u64 = 48;
+
+/// Time taken for one full circulation of a tank (a "major cycle").
+pub const CIRCULATION_TIME: u64 = WORD_TIME * WORDS_PER_TANK as u64;
+
+#[must_use]
+pub fn pulse_times_to_micros(pulse_times: u64) -> u64 {
+    pulse_times * PULSE_TIME_NANOS / 1000
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct SimulatedClock {
+    pulse_times: u64,
+}
+
+impl SimulatedClock {
+    /// Total simulated time elapsed, in pulse times.
+    #[must_use]
+    pub fn pulse_times(&self) -> u64 {
+        self.pulse_times
+    }
+
+    /// Total simulated time elapsed, in microseconds.
+    #[must_use]
+    pub fn micros(&self) -> u64 {
+        pulse_times_to_micros(self.pulse_times)
+    }
+
+    /// Index of the word (within its tank) that is currently at the output of
+    /// the tank. Every tank is driven by the same master clock, so this is the
+    /// same for all of them.
+    #[must_use]
+    pub fn phase(&self) -> usize {
+        ((self.pulse_times % CIRCULATION_TIME) / WORD_TIME) as usize
+    }
+
+    /// Number of pulse times until the word at the given *physical* address
+    /// starts coming out of its tank.
+    #[must_use]
+    pub fn latency(&self, physical_address: usize) -> u64 {
+        let slot = (physical_address % WORDS_PER_TANK) as u64 * WORD_TIME;
+        let position = self.pulse_times % CIRCULATION_TIME;
+
+        (slot + CIRCULATION_TIME - position) % CIRCULATION_TIME
+    }
+
+    /// Waits for the word at the given *physical* address to come around and
+    /// then transfers it, returning the number of pulse times spent waiting.
+    pub(crate) fn access(&mut self, physical_address: usize) -> u64 {
+        let wait = self.latency(physical_address);
+
+        self.advance(wait + WORD_TIME);
+
+        wait
+    }
+
+    pub fn advance(&mut self, pulse_times: u64) {
+        self.pulse_times += pulse_times;
+    }
+
+    pub fn reset(&mut self) {
+        self.pulse_times = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequential_access() {
+        let mut clock = SimulatedClock::default();
+
+        // words in consecutive slots of a tank come out one after another
+        for address in 0o0000..0o0010 {
+            assert_eq!(clock.access(address), 0);
+        }
+
+        assert_eq!(clock.pulse_times(), CIRCULATION_TIME);
+        assert_eq!(clock.phase(), 0);
+    }
+
+    #[test]
+    fn repeated_access() {
+        let mut clock = SimulatedClock::default();
+
+        assert_eq!(clock.access(0o1003), 3 * WORD_TIME);
+        // the same word was just read, so it has to come all the way around
+        assert_eq!(clock.access(0o1003), CIRCULATION_TIME - WORD_TIME);
+        // but a word in the same slot of a different tank has to as well
+        assert_eq!(clock.access(0o0013), CIRCULATION_TIME - WORD_TIME);
+    }
+}
@@ -0,0 +1,343 @@
+//! # Execution traces
+//! A trace records every order the machine carries out, one line per order, so
+//! that two runs of the same program can be compared. [`TraceWriter`] is an
+//! [`EdvacObserver`] that writes traces, [`read_trace`] reads them back and
+//! [`first_divergence`] finds where two of them part ways.
+//!
+//! A trace starts with the line `edvac-trace 1`, followed by one line per order:
+//! ```text
+//! 0000 E 1001 0003 1003 1003 r 1001 000000000000000 r 1003 000220200020045 w 1003 000220200020045
+//! 0001 A 0010 0010 0011 0002 r 0010 377777777777776 r 0010 377777777777776 w 0011 377777777777776 overflow
+//! ```
+//! That is the address the order came from (the contents of the Initial Address
+//! Register), the order itself as it would be written in a listing (with `?` for
+//! unused orders), then every word read (`r`) and written (`w`) by the order, in
+//! the order it happened. Words are the raw 44 bits, in octal. An order that
+//! exceeded the capacity of the machine ends with `overflow`, and one that
+//! faulted ends with `fault`.
+
+use std::{
+    fmt,
+    io::{self, BufRead, Write},
+    str::FromStr,
+};
+
+use log::error;
+
+use crate::{
+    observer::{EdvacEvent, EdvacObserver},
+    orders::{Order, OrderKind},
+    word::Word,
+};
+
+const HEADER: &str = "edvac-trace 1";
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TraceRecord {
+    pub address: usize,
+    pub order: Order,
+    pub reads: Vec<(usize, Word)>,
+    pub writes: Vec<(usize, Word)>,
+    pub overflow: bool,
+    pub fault: bool,
+}
+
+impl fmt::Display for TraceRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:0>4o} {} {:0>4o} {:0>4o} {:0>4o} {:0>4o}",
+            self.address,
+            self.order.kind.to_mneumonic().unwrap_or("?"),
+            self.order.addresses[0],
+            self.order.addresses[1],
+            self.order.addresses[2],
+            self.order.addresses[3],
+        )?;
+
+        for (access, words) in [("r", &self.reads), ("w", &self.writes)] {
+            for (address, word) in words {
+                write!(f, " {} {:0>4o} {:0>15o}", access, address, word.get_bits())?;
+            }
+        }
+
+        if self.overflow {
+            write!(f, " overflow")?;
+        }
+
+        if self.fault {
+            write!(f, " fault")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for TraceRecord {
+    type Err = ();
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        fn address(token: Option<&str>) -> Result<usize, ()> {
+            usize::from_str_radix(token.ok_or(())?, 8).map_err(|_| ())
+        }
+
+        let mut tokens = line.split_whitespace();
+
+        let address_register = address(tokens.next())?;
+        let kind = match tokens.next().ok_or(())? {
+            "?" => OrderKind::Unused,
+            mneumonic => OrderKind::from_mneumonic(mneumonic).ok_or(())?,
+        };
+
+        let mut addresses = [0; 4];
+        for order_address in &mut addresses {
+            *order_address = address(tokens.next())?;
+        }
+
+        let mut record = TraceRecord {
+            address: address_register,
+            order: Order { kind, addresses },
+            reads: Vec::new(),
+            writes: Vec::new(),
+            overflow: false,
+            fault: false,
+        };
+
+        while let Some(token) = tokens.next() {
+            match token {
+                "r" | "w" => {
+                    let address = address(tokens.next())?;
+                    let bits = u64::from_str_radix(tokens.next().ok_or(())?, 8).map_err(|_| ())?;
+
+                    let words = if token == "r" {
+                        &mut record.reads
+                    } else {
+                        &mut record.writes
+                    };
+                    words.push((address, Word::from_bits(bits)));
+                }
+                "overflow" => record.overflow = true,
+                "fault" => record.fault = true,
+                _ => return Err(()),
+            }
+        }
+
+        Ok(record)
+    }
+}
+
+/// Writes a trace of everything the machine it is attached to does.
+///
+/// Nothing reports errors back to an observer, so if writing fails, the error
+/// is logged and the rest of the trace is dropped. Writing to a file is much
+/// faster through a [`std::io::BufWriter`].
+pub struct TraceWriter<W: Write> {
+    writer: Option<W>,
+    pending: Option<TraceRecord>,
+}
+
+impl<W: Write> TraceWriter<W> {
+    #[must_use]
+    pub fn new(writer: W) -> TraceWriter<W> {
+        let mut trace = TraceWriter {
+            writer: Some(writer),
+            pending: None,
+        };
+        trace.write_line(HEADER);
+
+        trace
+    }
+
+    fn write_line(&mut self, line: impl fmt::Display) {
+        if let Some(writer) = &mut self.writer {
+            if let Err(error) = writeln!(writer, "{}", line) {
+                error!("Unable to write trace: {}", error);
+
+                self.writer = None;
+            }
+        }
+    }
+}
+
+impl<W: Write> EdvacObserver for TraceWriter<W> {
+    fn notify(&mut self, event: &EdvacEvent) {
+        match *event {
+            EdvacEvent::OrderFetched { address, order, .. } => {
+                self.pending = Some(TraceRecord {
+                    address,
+                    order,
+                    reads: Vec::new(),
+                    writes: Vec::new(),
+                    overflow: false,
+                    fault: false,
+                });
+            }
+            EdvacEvent::MemoryRead { address, word } => {
+                if let Some(record) = &mut self.pending {
+                    record.reads.push((address, word));
+                }
+            }
+            EdvacEvent::MemoryWrite { address, word } => {
+                if let Some(record) = &mut self.pending {
+                    record.writes.push((address, word));
+                }
+            }
+            EdvacEvent::Overflow { .. } => {
+                if let Some(record) = &mut self.pending {
+                    record.overflow = true;
+                }
+            }
+            EdvacEvent::OrderExecuted { .. } => {
+                if let Some(record) = self.pending.take() {
+                    self.write_line(record);
+                }
+            }
+            EdvacEvent::Faulted { .. } => {
+                if let Some(mut record) = self.pending.take() {
+                    record.fault = true;
+                    self.write_line(record);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum TraceError {
+    Io(io::Error),
+    NotATrace,
+    /// `line` (1-based) isn't a valid trace record.
+    BadRecord {
+        line: usize,
+    },
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TraceError::Io(error) => write!(f, "{}", error),
+            TraceError::NotATrace => write!(f, "not an EDVAC trace"),
+            TraceError::BadRecord { line } => write!(f, "line {}: bad trace record", line),
+        }
+    }
+}
+
+impl std::error::Error for TraceError {}
+
+impl From<io::Error> for TraceError {
+    fn from(error: io::Error) -> Self {
+        TraceError::Io(error)
+    }
+}
+
+/// Reads a trace written by [`TraceWriter`].
+pub fn read_trace(reader: impl BufRead) -> Result<Vec<TraceRecord>, TraceError> {
+    let mut lines = reader.lines();
+
+    if lines.next().transpose()?.as_deref() != Some(HEADER) {
+        return Err(TraceError::NotATrace);
+    }
+
+    lines
+        .enumerate()
+        .map(|(index, line)| {
+            line?
+                .parse()
+                .map_err(|()| TraceError::BadRecord { line: index + 2 })
+        })
+        .collect()
+}
+
+/// Index of the first record that differs between two traces, or `None` if they
+/// are the same. If one trace is a prefix of the other, they differ at the end
+/// of the shorter one.
+#[must_use]
+pub fn first_divergence(a: &[TraceRecord], b: &[TraceRecord]) -> Option<usize> {
+    match a.iter().zip(b).position(|(a, b)| a != b) {
+        Some(index) => Some(index),
+        None if a.len() != b.len() => Some(a.len().min(b.len())),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::BufReader,
+        sync::{Arc, Mutex},
+    };
+
+    use super::*;
+
+    use crate::{test_programs::dec_to_bin, Edvac};
+
+    /// A `Write` that can still be read after being handed to an observer.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn trace(input: Word) -> Vec<TraceRecord> {
+        let buffer = SharedBuffer::default();
+
+        let mut computer = Edvac::default();
+        dec_to_bin::load(&mut computer);
+        computer.low_speed_memory[1].write_word(input);
+        computer.attach_observer(Box::new(TraceWriter::new(buffer.clone())));
+
+        computer.initiate_pressed();
+        computer.continue_to_completion().unwrap();
+
+        let bytes = buffer.0.lock().unwrap().clone();
+        read_trace(BufReader::new(bytes.as_slice())).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let record = TraceRecord {
+            address: 0o1113,
+            order: inst!(A 0o1054 0o1052 0o1044 0o1050).into(),
+            reads: vec![
+                (0o1054, Word::from_bits(0o16)),
+                (0o1052, Word::from_bits(1)),
+            ],
+            writes: vec![(0o1044, Word::from_bits(0o14))],
+            overflow: true,
+            fault: false,
+        };
+
+        assert_eq!(
+            record.to_string(),
+            "1113 A 1054 1052 1044 1050 r 1054 000000000000016 r 1052 000000000000001 \
+             w 1044 000000000000014 overflow"
+        );
+        assert_eq!(record.to_string().parse(), Ok(record));
+    }
+
+    #[test]
+    fn divergence() {
+        let a = trace(Word::from_bits(
+            0b0010_0010_0010_0010_0010_0010_0010_0010_0010_0010_0000,
+        ));
+        let b = trace(Word::from_bits(
+            0b0010_0010_0010_0010_0010_0010_0010_0010_0010_0011_0000,
+        ));
+
+        assert_eq!(a[0].address, 0o0000);
+        assert_eq!(first_divergence(&a, &a), None);
+        assert_eq!(first_divergence(&a, &a[..10]), Some(10));
+
+        let index = first_divergence(&a, &b).unwrap();
+        assert_eq!(a[..index], b[..index]);
+        assert_ne!(a[index], b[index]);
+    }
+}
@@ -0,0 +1,271 @@
+//! # Wire transports
+//! Each wire spool sits on a handler that drags the wire past its head at a
+//! fixed speed, which is slow next to the delay lines, and reversing the wire
+//! means stopping it and starting it back up the other way.
+//!
+//! A [`WireTransport`] charges the simulated clock for every bit the wire
+//! moves, at the rates given by its [`TransportSpeeds`], and keeps track of how
+//! that time was spent so that the utilization of each spool can be reported
+//! after a run.
+//!
+//! None of the sources at hand give the speeds of the EDVAC's handlers, so
+//! none are built in: by default the wires take no time at all, as they did
+//! before transports were modeled, and the speeds have to be set per spool to
+//! time them.
+
+use crate::{
+    timing::SimulatedClock,
+    wire::{WireShift, WireSpool},
+    Edvac,
+};
+
+/// What a wire handler was doing while its wire moved.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WireActivity {
+    Reading,
+    Recording,
+    /// Moving the wire without reading or recording, like the "translate"
+    /// sub-order does.
+    Translating,
+    Rewinding,
+}
+
+/// How long a wire handler takes to do things, in pulse times. The default is
+/// no time at all; see the [module documentation](self).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct TransportSpeeds {
+    /// Time for one bit to pass the head, whether it is being read, recorded
+    /// or skipped over.
+    pub bit_time: u64,
+    /// Time per bit while rewinding, which is done at a higher speed.
+    pub rewind_bit_time: u64,
+    /// Time to stop the wire and start it moving in the other direction.
+    pub reversal_time: u64,
+}
+
+/// Time a spool has spent on each [`WireActivity`], in pulse times.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct TransportUsage {
+    pub reading: u64,
+    pub recording: u64,
+    pub translating: u64,
+    pub rewinding: u64,
+}
+
+impl TransportUsage {
+    /// Total time the spool has been busy, in pulse times.
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.reading + self.recording + self.translating + self.rewinding
+    }
+}
+
+/// What happens when a wire is run off of either of its ends.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum WireEndAction {
+    /// Fault the machine with [`crate::error::EdvacError::EndOfWire`] or
+    /// [`crate::error::EdvacError::BeginningOfWire`], leaving the wire at the end it ran
+    /// into.
+    #[default]
+    Halt,
+    /// Stop the wire at the end and carry on as though nothing happened.
+    /// Reading past the end gives blank bits, and whatever is recorded past it
+    /// is lost.
+    Stop,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct WireTransport {
+    pub speeds: TransportSpeeds,
+    pub end_action: WireEndAction,
+
+    usage: TransportUsage,
+    /// Whether the wire last moved backward.
+    backward: bool,
+}
+
+impl WireTransport {
+    #[must_use]
+    pub fn usage(&self) -> TransportUsage {
+        self.usage
+    }
+
+    /// Fraction of the time elapsed on `clock` that this spool has been busy,
+    /// between 0 and 1.
+    #[must_use]
+    pub fn utilization(&self, clock: &SimulatedClock) -> f64 {
+        match clock.pulse_times() {
+            0 => 0.0,
+            elapsed => self.usage.total() as f64 / elapsed as f64,
+        }
+    }
+
+    pub fn reset_usage(&mut self) {
+        self.usage = TransportUsage::default();
+    }
+
+    /// Accounts for the wire having moved by `shift` (the distance it actually
+    /// moved, after stopping at either end), returning the time it took.
+    pub(crate) fn moved(&mut self, shift: WireShift, activity: WireActivity) -> u64 {
+        let (backward, bits) = match shift {
+            WireShift::Forward(bits) => (false, bits),
+            WireShift::Backward(bits) => (true, bits),
+        };
+
+        if bits == 0 {
+            return 0;
+        }
+
+        let bit_time = match activity {
+            WireActivity::Rewinding => self.speeds.rewind_bit_time,
+            _ => self.speeds.bit_time,
+        };
+        let mut time = bits as u64 * bit_time;
+
+        if backward != self.backward {
+            time += self.speeds.reversal_time;
+            self.backward = backward;
+        }
+
+        *match activity {
+            WireActivity::Reading => &mut self.usage.reading,
+            WireActivity::Recording => &mut self.usage.recording,
+            WireActivity::Translating => &mut self.usage.translating,
+            WireActivity::Rewinding => &mut self.usage.rewinding,
+        } += time;
+
+        time
+    }
+
+    pub(crate) fn backward(&self) -> bool {
+        self.backward
+    }
+
+    /// Rebuilds a transport from what [`WireTransport::usage`] and
+    /// [`WireTransport::backward`] report, for snapshots.
+    pub(crate) fn with_parts(
+        speeds: TransportSpeeds,
+        usage: TransportUsage,
+        backward: bool,
+    ) -> WireTransport {
+        WireTransport {
+            speeds,
+            usage,
+            backward,
+            ..Default::default()
+        }
+    }
+}
+
+/// # Wire transports
+impl Edvac {
+    /// Rewinds a wire back to its beginning at the handler's rewind speed, as
+    /// an operator would between jobs. Wire 0 isn't a real wire, so rewinding
+    /// it does nothing.
+    pub fn rewind_wire(&mut self, wire_spool: WireSpool) {
+        if let Ok(index) = usize::try_from(wire_spool) {
+            let distance = self.low_speed_memory[index].index();
+
+            self.translate_wire(
+                wire_spool,
+                WireShift::Backward(distance),
+                WireActivity::Rewinding,
+            )
+            .expect("rewinding stops right at the beginning of the wire");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        test_programs::dec_to_bin,
+        word::{Word, BIT_WIDTH},
+    };
+
+    /// Made up for the tests, not the EDVAC's.
+    const SPEEDS: TransportSpeeds = TransportSpeeds {
+        bit_time: 100,
+        rewind_bit_time: 20,
+        reversal_time: 20_000,
+    };
+
+    #[test]
+    fn usage() {
+        let mut transport = WireTransport {
+            speeds: SPEEDS,
+            ..Default::default()
+        };
+        let speeds = transport.speeds;
+
+        assert_eq!(
+            transport.moved(WireShift::Forward(10), WireActivity::Reading),
+            10 * speeds.bit_time
+        );
+        assert_eq!(
+            transport.moved(WireShift::Forward(10), WireActivity::Recording),
+            10 * speeds.bit_time
+        );
+        // changing direction means stopping and starting again
+        assert_eq!(
+            transport.moved(WireShift::Backward(20), WireActivity::Rewinding),
+            20 * speeds.rewind_bit_time + speeds.reversal_time
+        );
+
+        assert_eq!(
+            transport.usage(),
+            TransportUsage {
+                reading: 10 * speeds.bit_time,
+                recording: 10 * speeds.bit_time,
+                translating: 0,
+                rewinding: 20 * speeds.rewind_bit_time + speeds.reversal_time,
+            }
+        );
+    }
+
+    #[test]
+    fn utilization() {
+        let mut computer = Edvac::default();
+        dec_to_bin::load(&mut computer);
+        computer.low_speed_memory[1].write_word(Word::from_bits(
+            0b0010_0010_0010_0010_0010_0010_0010_0010_0010_0010_0000,
+        ));
+        for transport in &mut computer.wire_transports {
+            transport.speeds = SPEEDS;
+        }
+
+        computer.initiate_pressed();
+        computer.continue_to_completion().unwrap();
+
+        // the program reads a single word off of Wire 2
+        let speeds = SPEEDS;
+        assert_eq!(
+            computer.wire_transports[0].usage(),
+            TransportUsage::default()
+        );
+        assert_eq!(
+            computer.wire_transports[1].usage().reading,
+            BIT_WIDTH as u64 * speeds.bit_time
+        );
+
+        let utilization = computer.wire_transports[1].utilization(&computer.clock);
+        assert!(utilization > 0.0 && utilization < 1.0);
+
+        // untimed wires are never busy
+        let mut untimed = Edvac::default();
+        dec_to_bin::load(&mut untimed);
+        untimed.initiate_pressed();
+        untimed.continue_to_completion().unwrap();
+        assert_eq!(untimed.wire_transports[1].usage().total(), 0);
+
+        let before = computer.clock.pulse_times();
+        computer.rewind_wire(WireSpool::Two);
+        assert_eq!(computer.low_speed_memory[1].index(), 0);
+        assert_eq!(
+            computer.clock.pulse_times() - before,
+            BIT_WIDTH as u64 * speeds.rewind_bit_time + speeds.reversal_time
+        );
+    }
+}
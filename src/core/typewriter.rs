@@ -0,0 +1,167 @@
+//! # Typewriter
+//! Wire 0 isn't a wire. Reading from it reads the Auxiliary Input switches,
+//! and recording to it types the words on the console typewriter, one word to
+//! a line, instead. Words can be typed either as they are written in a listing,
+//! in octal:
+//! ```text
+//! +000 0000 0000 0001
+//! -000 0000 0000 0002
+//! ```
+//! or as the fractions they stand for, in decimal:
+//! ```text
+//! +.5000000000000
+//! -.2500000000000
+//! ```
+//! Everything typed is kept in [`Typewriter::output`] until it is cleared.
+//!
+//! None of the sources at hand show what the console typewriter actually
+//! typed, so both layouts are assumed: the octal one is the listing format read
+//! by the [assembler](crate::assembler), and the decimal one is the usual way
+//! of writing a fraction.
+
+use crate::word::Word;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TypewriterMode {
+    #[default]
+    Octal,
+    DecimalFraction,
+}
+
+/// Digits after the point in [`TypewriterMode::DecimalFraction`], enough to
+/// tell any two words apart.
+const FRACTION_DIGITS: usize = 13;
+
+/// Formats a word as it would be typed in `mode`, without the newline.
+#[must_use]
+pub fn format_word(word: Word, mode: TypewriterMode) -> String {
+    match mode {
+        TypewriterMode::Octal => word.to_octal(),
+        // no word is close enough to 1 to be typed as one with this many digits
+        TypewriterMode::DecimalFraction => word.to_decimal(FRACTION_DIGITS),
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct Typewriter {
+    pub mode: TypewriterMode,
+
+    output: String,
+}
+
+impl Typewriter {
+    /// Everything typed since the typewriter was last cleared.
+    #[must_use]
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Hands back everything typed so far, clearing it.
+    pub fn take_output(&mut self) -> String {
+        std::mem::take(&mut self.output)
+    }
+
+    pub fn clear(&mut self) {
+        self.output.clear();
+    }
+
+    pub(crate) fn type_word(&mut self, word: Word) {
+        self.output.push_str(&format_word(word, self.mode));
+        self.output.push('\n');
+    }
+
+    /// Cuts the output back to `length` bytes, for undoing steps.
+    pub(crate) fn truncate(&mut self, length: usize) {
+        self.output.truncate(length);
+    }
+
+    /// A typewriter that has already typed `output`, for snapshots.
+    pub(crate) fn with_output(mode: TypewriterMode, output: String) -> Typewriter {
+        Typewriter { mode, output }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{wire::WireSpool, word::U43_MAX, Edvac};
+
+    #[test]
+    fn formats() {
+        let half = Word::from_bits(1 << 43);
+        let quarter = -Word::from_bits(1 << 42);
+        let smallest = Word::from_bits(0b10);
+
+        assert_eq!(
+            format_word(half, TypewriterMode::Octal),
+            "+100 0000 0000 0000"
+        );
+        assert_eq!(
+            format_word(quarter, TypewriterMode::Octal),
+            "-040 0000 0000 0000"
+        );
+
+        assert_eq!(
+            format_word(half, TypewriterMode::DecimalFraction),
+            "+.5000000000000"
+        );
+        assert_eq!(
+            format_word(quarter, TypewriterMode::DecimalFraction),
+            "-.2500000000000"
+        );
+        assert_eq!(
+            format_word(smallest, TypewriterMode::DecimalFraction),
+            "+.0000000000001"
+        );
+        assert_eq!(
+            format_word(
+                Word::from_bits(U43_MAX << 1),
+                TypewriterMode::DecimalFraction
+            ),
+            "+.9999999999999"
+        );
+    }
+
+    #[test]
+    fn typing() {
+        let mut computer = Edvac::default();
+        computer
+            .high_speed_memory
+            .load(vec![
+                // record 0o0010 to 0o0011 onto Wire 0
+                (0o0000, inst!(W 0o0010 0o0100 0o0011 0o0001)),
+                (0o0001, inst!(H 0o0000 0o0000 0o0000 0o0000)),
+                (0o0010, 1.try_into().unwrap()),
+                (0o0011, (-2).try_into().unwrap()),
+            ])
+            .unwrap();
+
+        computer.initiate_pressed();
+        computer.continue_to_completion().unwrap();
+        assert_eq!(
+            computer.typewriter.output(),
+            "+000 0000 0000 0001\n-000 0000 0000 0002\n"
+        );
+        // none of the real wires were touched
+        assert!(computer
+            .low_speed_memory
+            .iter()
+            .all(|wire| wire.extent() == 0));
+
+        assert_eq!(computer.typewriter.take_output().lines().count(), 2);
+        assert_eq!(computer.typewriter.output(), "");
+
+        computer.typewriter.mode = TypewriterMode::DecimalFraction;
+        computer.halt(0o0000);
+        computer.initiate_pressed();
+        computer.continue_to_completion().unwrap();
+        assert_eq!(
+            computer.typewriter.output(),
+            "+.0000000000001\n-.0000000000002\n"
+        );
+
+        // Wire 0 still can't be read as a wire
+        assert!(computer.read_address_from_wire(WireSpool::Zero).is_err());
+    }
+}
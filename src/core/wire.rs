@@ -1,12 +1,42 @@
+//! # Wires
+//! The EDVAC's low-speed memory: spools of magnetic wire, read and recorded one
+//! bit after another as the wire is moved past a head.
+//!
+//! ## File format
+//! Wires are saved to disk (with [`Wire::save`]) in a simple binary format, in
+//! which every integer is little-endian:
+//!
+//! | Size         | Contents                                                    |
+//! |--------------|-------------------------------------------------------------|
+//! | 8 bytes      | [`WIRE_MAGIC`]                                              |
+//! | 2 bytes      | format version, [`WIRE_FILE_VERSION`]                       |
+//! | 8 bytes      | position of the head ([`Wire::index`]), in bits             |
+//! | 8 bytes      | written extent ([`Wire::extent`]), in bits                  |
+//! | 4 bytes      | length of the label, in bytes                               |
+//! | ...          | the label ([`Wire::label`]), in UTF-8                       |
+//! | ...          | the first `extent` bits of the wire, 8 to a byte, LSB first |
+//!
+//! Everything past the written extent is blank, so it isn't stored.
+
+use std::io::{self, Read, Write};
+
 use bitvec::prelude::*;
-use log::error;
 
 use crate::{
     high_speed_memory::ADDRESS_WIDTH,
     word::{Word, BIT_WIDTH},
 };
 
-const WIRE_SIZE: usize = 50000 * BIT_WIDTH;
+pub(crate) const WIRE_SIZE: usize = 50000 * BIT_WIDTH;
+
+/// Every saved wire starts with these bytes.
+pub const WIRE_MAGIC: [u8; 8] = *b"EDVACWIR";
+
+/// Bumped whenever the layout of a saved wire changes.
+pub const WIRE_FILE_VERSION: u16 = 1;
+
+/// Labels are for people, so they don't need to be long.
+const MAX_LABEL_LENGTH: usize = 1 << 16;
 
 #[derive(Eq, PartialEq, Debug, Clone, Copy)]
 pub enum WireSpool {
@@ -52,7 +82,14 @@ impl From<WireSpool> for String {
     }
 }
 
-#[derive(Copy, Clone)]
+/// The ends of a wire, which it can't be moved past.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum WireEnd {
+    Beginning,
+    End,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum WireShift {
     Forward(usize),
     Backward(usize),
@@ -62,30 +99,45 @@ pub enum WireShift {
 pub struct Wire {
     bits: BitBox,
     index: usize,
+
+    label: String,
+    /// One past the furthest bit ever written.
+    extent: usize,
 }
 
 impl Wire {
-    pub fn translate(&mut self, shift: WireShift) {
+    /// Moves the wire past the head. A wire can't be moved past either of its
+    /// ends; if asked to, it stops there and says which end it ran into.
+    pub fn translate(&mut self, shift: WireShift) -> Result<(), WireEnd> {
         match shift {
             WireShift::Forward(shift) => {
-                self.index += shift;
-
-                if self.index >= WIRE_SIZE {
+                if self.index + shift >= WIRE_SIZE {
                     self.index = WIRE_SIZE - 1;
 
-                    error!("Attempted to shift a wire past its end point.");
+                    return Err(WireEnd::End);
                 }
+
+                self.index += shift;
             }
             WireShift::Backward(shift) => {
                 if self.index < shift {
                     self.index = 0;
 
-                    error!("Attempted to shift a wire past its beginning point.");
-                } else {
-                    self.index -= shift;
+                    return Err(WireEnd::Beginning);
                 }
+
+                self.index -= shift;
             }
         }
+
+        Ok(())
+    }
+
+    /// Number of bits from the head to the end of the wire. Reading or
+    /// recording more than this runs off the end.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        WIRE_SIZE - self.index
     }
 
     fn read(&mut self, length: usize) -> Vec<bool> {
@@ -110,25 +162,93 @@ impl Wire {
             .fold(0, |acc, &bit| acc * 2 + bit as usize)
     }
 
+    /// Records a word at the head. Whatever doesn't fit before the end of the
+    /// wire is lost.
     pub fn write_word(&mut self, word: Word) {
-        let mut bits = word.get_bits();
-
-        for i in 0..=BIT_WIDTH {
-            let bit = (bits & 0b1) == 0b1;
-            bits >>= 1;
-            self.bits.set(self.index + i, bit);
-        }
+        self.set_bits_at(self.index, word.get_bits(), BIT_WIDTH);
     }
 
     /// Per prelim. report pg. 11, the EDVAC can read but not write addresses
     pub(crate) fn write_address(&mut self, address: usize) {
-        let mut bits = address as u64;
+        self.set_bits_at(self.index, address as u64, ADDRESS_WIDTH);
+    }
 
-        for i in 0..=ADDRESS_WIDTH {
-            let bit = (bits & 0b1) == 0b1;
+    /// Position of the read/write head, in bits from the start of the wire.
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Moves the read/write head straight to `index`, without "translating"
+    /// the wire.
+    pub(crate) fn seek(&mut self, index: usize) {
+        self.index = index.min(WIRE_SIZE - 1);
+    }
+
+    /// A name for what is on the wire, for people's benefit. The machine
+    /// ignores it.
+    #[must_use]
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn set_label(&mut self, label: impl Into<String>) {
+        self.label = label.into();
+    }
+
+    /// How much of the wire has been written to, in bits from the start. Every
+    /// bit past this is blank.
+    #[must_use]
+    pub fn extent(&self) -> usize {
+        self.extent
+    }
+
+    /// Puts the written extent back to what it was, for the journal.
+    pub(crate) fn set_extent(&mut self, extent: usize) {
+        self.extent = extent.min(WIRE_SIZE);
+    }
+
+    /// Reads `length` bits starting at `index`, without moving the wire.
+    pub(crate) fn bits_at(&self, index: usize, length: usize) -> u64 {
+        self.bits[index..(index + length).min(WIRE_SIZE)]
+            .iter()
+            .by_vals()
+            .rev()
+            .fold(0, |acc, bit| acc * 2 + bit as u64)
+    }
+
+    /// Writes the low `length` bits of `bits` starting at `index`, without
+    /// moving the wire. The opposite of [`Wire::bits_at`].
+    pub(crate) fn set_bits_at(&mut self, index: usize, mut bits: u64, length: usize) {
+        let end = (index + length).min(WIRE_SIZE);
+
+        for i in index..end {
+            self.bits.set(i, (bits & 0b1) == 0b1);
             bits >>= 1;
-            self.bits.set(self.index + i, bit);
         }
+
+        self.extent = self.extent.max(end);
+    }
+
+    /// Reads the whole wire back as a program, in the format written by
+    /// [`Wire::with_program`]. Reading stops after the last address/word pair
+    /// with any bits set, so a trailing `0000 +000 0000 0000 0000` is lost.
+    #[must_use]
+    pub fn program(&self) -> Vec<(usize, Word)> {
+        const PAIR_WIDTH: usize = ADDRESS_WIDTH + BIT_WIDTH;
+
+        let length = self.bits.last_one().map_or(0, |last| last / PAIR_WIDTH + 1);
+
+        (0..length)
+            .map(|pair| {
+                let index = pair * PAIR_WIDTH;
+
+                (
+                    self.bits_at(index, ADDRESS_WIDTH) as usize,
+                    Word::from_bits(self.bits_at(index + ADDRESS_WIDTH, BIT_WIDTH)),
+                )
+            })
+            .collect()
     }
 
     pub fn with_program(listing: Vec<(usize, Word)>) -> Wire {
@@ -146,8 +266,17 @@ impl Wire {
         wire
     }
 
-    pub fn with_bytes(bytes: Vec<u8>) -> Wire {
-        let mut wire = Wire::default();
+    /// A wire holding `bytes`, lowest bit first. Fails if they don't fit on a
+    /// spool.
+    pub fn with_bytes(bytes: Vec<u8>) -> io::Result<Wire> {
+        if bytes.len() * 8 > WIRE_SIZE {
+            return Err(invalid("wire is longer than a spool"));
+        }
+
+        let mut wire = Wire {
+            extent: bytes.len() * 8,
+            ..Default::default()
+        };
 
         let mut index = 0;
         bytes.iter().for_each(|&byte| {
@@ -161,7 +290,86 @@ impl Wire {
             });
         });
 
-        wire
+        Ok(wire)
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// # Files
+/// See the [module documentation](self) for the format.
+impl Wire {
+    pub fn save(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(&WIRE_MAGIC)?;
+        writer.write_all(&WIRE_FILE_VERSION.to_le_bytes())?;
+        writer.write_all(&(self.index as u64).to_le_bytes())?;
+        writer.write_all(&(self.extent as u64).to_le_bytes())?;
+        writer.write_all(&(self.label.len() as u32).to_le_bytes())?;
+        writer.write_all(self.label.as_bytes())?;
+
+        let bytes: Vec<u8> = self.bits[..self.extent]
+            .chunks(8)
+            .map(|chunk| chunk.load_le::<u8>())
+            .collect();
+        writer.write_all(&bytes)?;
+
+        writer.flush()
+    }
+
+    pub fn load(mut reader: impl Read) -> io::Result<Wire> {
+        fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+            let mut bytes = [0; 8];
+            reader.read_exact(&mut bytes)?;
+
+            Ok(u64::from_le_bytes(bytes))
+        }
+
+        let mut magic = [0; WIRE_MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != WIRE_MAGIC {
+            return Err(invalid("not an EDVAC wire"));
+        }
+
+        let mut version = [0; 2];
+        reader.read_exact(&mut version)?;
+        if u16::from_le_bytes(version) != WIRE_FILE_VERSION {
+            return Err(invalid("unsupported wire format version"));
+        }
+
+        let index = read_u64(&mut reader)?;
+        let extent = read_u64(&mut reader)?;
+        if index >= WIRE_SIZE as u64 || extent > WIRE_SIZE as u64 {
+            return Err(invalid("wire is longer than a spool"));
+        }
+
+        let mut label_length = [0; 4];
+        reader.read_exact(&mut label_length)?;
+        let label_length = u32::from_le_bytes(label_length) as usize;
+        if label_length > MAX_LABEL_LENGTH {
+            return Err(invalid("wire label is too long"));
+        }
+
+        let mut label = vec![0; label_length];
+        reader.read_exact(&mut label)?;
+        let label = String::from_utf8(label).map_err(|_| invalid("wire label isn't UTF-8"))?;
+
+        let extent = extent as usize;
+        let mut bytes = vec![0; extent.div_ceil(8)];
+        reader.read_exact(&mut bytes)?;
+
+        let mut wire = Wire {
+            index: index as usize,
+            label,
+            extent,
+            ..Default::default()
+        };
+        for (i, &byte) in bytes.iter().enumerate() {
+            wire.set_bits_at(i * 8, u64::from(byte), 8.min(extent - i * 8));
+        }
+
+        Ok(wire)
     }
 }
 
@@ -170,6 +378,71 @@ impl Default for Wire {
         Wire {
             bits: bitbox![0; WIRE_SIZE],
             index: 0,
+
+            label: String::new(),
+            extent: 0,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_load() {
+        let mut wire = Wire::with_program(vec![
+            (0o0001, Word::from_bits(0o123)),
+            (0o0002, Word::from_bits(0o456 << 30)),
+        ]);
+        wire.set_label("test program");
+        wire.translate(WireShift::Forward(ADDRESS_WIDTH)).unwrap();
+
+        let mut file = Vec::new();
+        wire.save(&mut file).unwrap();
+        // only the written part of the wire is saved
+        assert!(file.len() < 100);
+
+        let loaded = Wire::load(file.as_slice()).unwrap();
+        assert_eq!(loaded.label(), "test program");
+        assert_eq!(loaded.index(), ADDRESS_WIDTH);
+        assert_eq!(loaded.extent(), wire.extent());
+        assert_eq!(loaded.program(), wire.program());
+
+        file[0] = b'X';
+        assert!(Wire::load(file.as_slice()).is_err());
+    }
+
+    #[test]
+    fn bytes() {
+        let wire = Wire::with_bytes(vec![0b0000_0101; WIRE_SIZE / 8]).unwrap();
+        assert_eq!(wire.extent(), WIRE_SIZE);
+        assert_eq!(wire.bits_at(0, 4), 0b0101);
+
+        assert!(Wire::with_bytes(vec![0; WIRE_SIZE / 8 + 1]).is_err());
+    }
+
+    #[test]
+    fn ends() {
+        let mut wire = Wire::default();
+
+        assert_eq!(
+            wire.translate(WireShift::Backward(1)),
+            Err(WireEnd::Beginning)
+        );
+        assert_eq!(wire.index(), 0);
+
+        wire.translate(WireShift::Forward(WIRE_SIZE - BIT_WIDTH))
+            .unwrap();
+        assert_eq!(wire.remaining(), BIT_WIDTH);
+        wire.write_word(Word::from_bits(1 << 43 | 1));
+        assert_eq!(wire.read_word(), Word::from_bits(1 << 43 | 1));
+        assert_eq!(wire.extent(), WIRE_SIZE);
+
+        assert_eq!(
+            wire.translate(WireShift::Forward(BIT_WIDTH)),
+            Err(WireEnd::End)
+        );
+        assert_eq!(wire.index(), WIRE_SIZE - 1);
+    }
+}
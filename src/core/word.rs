@@ -1,32 +1,73 @@
+//! # Words
+//! A word is 44 bits: a sign in the lowest bit, 1 for negative, and a 43-bit
+//! magnitude above it. That leaves two zeros, +0 and -0, and per the prelim.
+//! report pg. 37, "The computer will decode either -0 or +0 as 0, but it will
+//! encode 0 only as +0":
+//! - every arithmetic operation here reads -0 exactly as it reads +0, so
+//!   swapping one for the other in an operand never changes a result;
+//! - every word an arithmetic operation gives back that comes to zero is +0,
+//!   including either half of a product or quotient;
+//! - [`Word::is_negative`] asks about the value, which -0 doesn't have, and
+//!   [`Word::is_sign_negative`] about the sign bit.
+//!
+//! Operations on the bits rather than the value, like [`Word::set_sign`] and
+//! negation, move signs around as they are and can leave -0 behind. The
+//! `Extract` order moves signs as they are too, except that a whole word it
+//! transfers that comes to zero is stored as +0.
+//!
+//! ## Fractions
+//! The same bits can be read as an integer, the way [`TryFrom<i64>`] and
+//! `i64::from` read them, or as a fraction with the binary point just above the
+//! sign, the way the machine's programs mostly used them: a magnitude of `m` is
+//! `m / 2^43`, so words hold fractions strictly between -1 and 1 in steps of
+//! 2^-43. [`Word::from_f64`], [`Word::from_fraction`] and [`Word::from_decimal`]
+//! give the nearest word to a number, rounding one exactly halfway between two
+//! words away from zero, and refuse a number that would round to ±1.
+//! [`Word::to_f64`] and [`Word::to_fraction`] are exact, and
+//! [`Word::to_decimal`] rounds its last digit half up. [`Word::to_scaled`] and
+//! [`Word::from_scaled`] put the binary point `point` places further down
+//! instead, for numbers scaled by a power of two: 43 places down makes them
+//! integers.
+
 use std::ops::Neg;
 
 pub const BIT_WIDTH: usize = 44;
 pub const U43_MAX: u64 = 2_u64.pow(43) - 1;
 
+/// Places after the binary point of a word read as a fraction.
+pub const FRACTION_BITS: u32 = 43;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Word {
     rep: u64,
-    value: i64,
 }
 
 impl Word {
+    /// Sets the sign bit, even of a zero.
     pub fn set_sign(&mut self, negative: bool) {
         if negative {
             self.rep |= 0b1;
-            if !self.value.is_negative() {
-                self.value = -self.value;
-            }
         } else {
             self.rep &= !0b1;
-            if !self.value.is_positive() {
-                self.value = -self.value;
-            }
         }
     }
 
+    /// Whether the value of the word is below zero, which -0 isn't.
     #[must_use]
     pub fn is_negative(&self) -> bool {
-        self.value < 0
+        self.value() < 0
+    }
+
+    /// Whether the sign bit is set, as it is for -0.
+    #[must_use]
+    pub fn is_sign_negative(&self) -> bool {
+        self.rep & 0b1 == 0b1
+    }
+
+    /// Whether the word is +0 or -0.
+    #[must_use]
+    pub fn is_zero(&self) -> bool {
+        self.magnitude() == 0
     }
 
     #[must_use]
@@ -36,22 +77,34 @@ impl Word {
 
     pub fn set_bits(&mut self, value: u64) {
         self.rep = value;
-        self.value = Word::from_bits_to_i64(value);
+    }
+
+    fn magnitude(&self) -> u64 {
+        self.rep >> 1
+    }
+
+    fn value(&self) -> i64 {
+        Word::from_bits_to_i64(self.rep)
+    }
+
+    /// Encodes a result, only ever as +0 if it's zero.
+    fn encode(negative: bool, magnitude: u64) -> Word {
+        Word::from_bits(magnitude << 1 | u64::from(negative && magnitude != 0))
     }
 
     #[must_use]
     pub fn overflowing_add(&self, rhs: Word) -> (Word, bool) {
-        let value = self.value + rhs.value;
+        let value = self.value() + rhs.value();
 
         if value.unsigned_abs() > U43_MAX {
             // On pgs. 40, 41, 42 of prelim. report they mentioned that the sign
             // of the sum is always correct; enforce this.
-            let mut word: Word = (value - U43_MAX as i64).try_into().unwrap();
-            word.set_sign(value > 0);
-
-            (word, true)
+            (
+                Word::encode(value < 0, value.unsigned_abs() - U43_MAX),
+                true,
+            )
         } else {
-            (value.try_into().unwrap(), false)
+            (Word::encode(value < 0, value.unsigned_abs()), false)
         }
     }
 
@@ -62,56 +115,51 @@ impl Word {
 
     #[must_use]
     pub fn mul(&self, rhs: Word) -> (Word, Word) {
-        let result = i128::from(self.value) * i128::from(rhs.value);
-
-        let mut most_significant_word: Word = (((result.abs() >> 43) & i128::from(U43_MAX)) as i64)
-            .try_into()
-            .unwrap();
-        let mut least_significant_word: Word = ((result.abs() & i128::from(U43_MAX)) as i64)
-            .try_into()
-            .unwrap();
-
-        most_significant_word.set_sign(result < 0);
-        least_significant_word.set_sign(result < 0);
+        let result = i128::from(self.value()) * i128::from(rhs.value());
+        let magnitude = result.unsigned_abs();
 
-        (most_significant_word, least_significant_word)
+        (
+            Word::encode(result < 0, (magnitude >> 43) as u64 & U43_MAX),
+            Word::encode(result < 0, magnitude as u64 & U43_MAX),
+        )
     }
 
     #[must_use]
     pub fn overflowing_div(&self, rhs: Word) -> (Word, Word, bool) {
-        let mut a = i128::from(self.value) << 43;
-        let b = i128::from(rhs.value);
+        let a = i128::from(self.value()) << 43;
+        let b = i128::from(rhs.value());
 
-        let (most_significant_half, mut overflowed) = a.overflowing_div(b);
-        if most_significant_half > i128::from(U43_MAX) {
+        // dividing by zero is just the most extreme case of the divisor being
+        // smaller than the dividend
+        let (most_significant_half, mut overflowed) = if b == 0 {
+            (a, true)
+        } else {
+            a.overflowing_div(b)
+        };
+        if most_significant_half.abs() > i128::from(U43_MAX) {
             overflowed = true;
         }
 
-        // weird stuff happens with division overflows, see prelim. report pages
-        // 49-52
-        let (mut most_significant_word, mut least_significant_word) = if overflowed {
-            (Word::from_bits(U43_MAX << 1), Word::from_bits(U43_MAX << 1))
-        } else {
-            a = (a % b) << 43;
-
-            let least_significant_half = a.overflowing_div(b).0;
+        if overflowed {
+            // weird stuff happens with division overflows, see prelim. report
+            // pages 49-52; the words still get the sign of the quotient
+            let negative = self.is_negative() != rhs.is_negative();
 
-            let most_significant_word: Word = ((most_significant_half.abs() & i128::from(U43_MAX))
-                as i64)
-                .try_into()
-                .unwrap();
-            let least_significant_word: Word =
-                ((least_significant_half.abs() & i128::from(U43_MAX)) as i64)
-                    .try_into()
-                    .unwrap();
-
-            (most_significant_word, least_significant_word)
-        };
+            return (
+                Word::encode(negative, U43_MAX),
+                Word::encode(negative, U43_MAX),
+                true,
+            );
+        }
 
-        most_significant_word.set_sign(most_significant_half < 0);
-        least_significant_word.set_sign(most_significant_half < 0);
+        let least_significant_half = ((a % b) << 43) / b;
+        let negative = most_significant_half < 0;
 
-        (most_significant_word, least_significant_word, overflowed)
+        (
+            Word::encode(negative, most_significant_half.unsigned_abs() as u64),
+            Word::encode(negative, least_significant_half.unsigned_abs() as u64),
+            false,
+        )
     }
 
     fn from_bits_to_i64(rep: u64) -> i64 {
@@ -123,20 +171,17 @@ impl Word {
 
     #[must_use]
     pub fn from_bits(value: u64) -> Word {
-        Word {
-            rep: value,
-            value: Word::from_bits_to_i64(value),
-        }
+        Word { rep: value }
     }
 }
 
 impl Neg for Word {
     type Output = Word;
 
+    /// Flips the sign bit, so -(+0) is -0.
     fn neg(self) -> Self::Output {
         Word {
             rep: self.rep ^ 0b1,
-            value: -self.value,
         }
     }
 }
@@ -151,14 +196,190 @@ impl TryFrom<i64> for Word {
         if abs > U43_MAX {
             Err("Value is too large")
         } else {
-            // From preliminary report, pg. 37: "The computer will decode either
-            // -0 or +0 as 0, but it will encode 0 only as +0"
-            Ok(Word {
-                rep: (abs << 1) | u64::from(value.is_negative()),
-                value,
-            })
+            Ok(Word::encode(value.is_negative(), abs))
+        }
+    }
+}
+
+impl From<Word> for i64 {
+    // sign-magnitude 44-bit integer => 2's complement 64-bit integer, so -0
+    // becomes 0
+    fn from(word: Word) -> Self {
+        word.value()
+    }
+}
+
+/// # Fractions
+impl Word {
+    /// Rounds `bits`, a magnitude with one more place after the point than fits
+    /// in a word, half away from zero. `None` if that rounds up to 1.
+    fn round_fraction(negative: bool, bits: u128) -> Option<Word> {
+        let magnitude = (bits + 1) >> 1;
+
+        (magnitude <= u128::from(U43_MAX)).then(|| Word::encode(negative, magnitude as u64))
+    }
+
+    /// The nearest word to `value`; `None` if it isn't strictly between -1 and
+    /// 1 once rounded.
+    #[must_use]
+    pub fn from_f64(value: f64) -> Option<Word> {
+        if !value.is_finite() || value.abs() >= 1.0 {
+            return None;
+        }
+
+        // scaling by a power of two is exact
+        let bits = (value.abs() * 2_f64.powi(FRACTION_BITS as i32 + 1)) as u128;
+
+        Word::round_fraction(value < 0.0, bits)
+    }
+
+    /// The word as a fraction. Every word is exactly some `f64`, and -0 is 0.
+    #[must_use]
+    pub fn to_f64(self) -> f64 {
+        self.value() as f64 / 2_f64.powi(FRACTION_BITS as i32)
+    }
+
+    /// The nearest word to `numerator / denominator`; `None` if the denominator
+    /// is 0 or the fraction isn't strictly between -1 and 1 once rounded.
+    #[must_use]
+    pub fn from_fraction(numerator: i64, denominator: i64) -> Option<Word> {
+        if denominator == 0 {
+            return None;
+        }
+
+        let bits = (u128::from(numerator.unsigned_abs()) << (FRACTION_BITS + 1))
+            / u128::from(denominator.unsigned_abs());
+
+        Word::round_fraction((numerator < 0) != (denominator < 0), bits)
+    }
+
+    /// The word as a fraction in lowest terms, numerator and denominator. The
+    /// denominator is a power of two.
+    #[must_use]
+    pub fn to_fraction(self) -> (i64, u64) {
+        if self.is_zero() {
+            return (0, 1);
+        }
+
+        let shift = self.magnitude().trailing_zeros();
+
+        (self.value() >> shift, 1 << (FRACTION_BITS - shift))
+    }
+
+    /// The nearest word to a decimal fraction like `.375`, `-0.1` or `+.05`,
+    /// with any number of digits.
+    pub fn from_decimal(text: &str) -> Result<Word, &'static str> {
+        let (negative, text) = match text.strip_prefix('-') {
+            Some(text) => (true, text),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+
+        if (whole.is_empty() && fraction.is_empty())
+            || !whole
+                .bytes()
+                .chain(fraction.bytes())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err("Not a decimal fraction");
+        }
+        if whole.bytes().any(|c| c != b'0') {
+            return Err("Value is too large");
+        }
+
+        // doubling the digits after the point brings the bits of the fraction
+        // in front of it, one at a time
+        let mut digits: Vec<u8> = fraction.bytes().map(|c| c - b'0').collect();
+        let mut bits = 0;
+        for _ in 0..=FRACTION_BITS {
+            let mut carry = 0;
+            for digit in digits.iter_mut().rev() {
+                let doubled = *digit * 2 + carry;
+                *digit = doubled % 10;
+                carry = doubled / 10;
+            }
+
+            bits = bits << 1 | u128::from(carry);
+        }
+
+        Word::round_fraction(negative, bits).ok_or("Value is too large")
+    }
+
+    /// The word as a number is written in a listing: the sign bit, then the
+    /// magnitude in octal, in a group of three digits and three of four, like
+    /// `-120 0000 0000 0000`.
+    #[must_use]
+    pub fn to_octal(self) -> String {
+        let magnitude = self.rep >> 1;
+
+        format!(
+            "{}{:0>3o} {:0>4o} {:0>4o} {:0>4o}",
+            if self.is_sign_negative() { '-' } else { '+' },
+            magnitude >> 36,
+            (magnitude >> 24) & 0o7777,
+            (magnitude >> 12) & 0o7777,
+            magnitude & 0o7777,
+        )
+    }
+
+    /// The word as a decimal fraction with `digits` digits after the point and
+    /// the sign of its value in front, like `+.3750`, so -0 comes out as +0.
+    /// The last digit is rounded half up, which can carry all the way into a
+    /// `1` in front of the point.
+    #[must_use]
+    pub fn to_decimal(self, digits: usize) -> String {
+        let sign = if self.is_negative() { '-' } else { '+' };
+
+        // multiplying by ten brings the next digit in front of the point
+        let mut fraction = self.magnitude() & U43_MAX;
+        let mut decimals = Vec::with_capacity(digits);
+        for _ in 0..digits {
+            fraction *= 10;
+            decimals.push((fraction >> FRACTION_BITS) as u8);
+            fraction &= U43_MAX;
+        }
+
+        let mut carry = fraction >= 1 << (FRACTION_BITS - 1);
+        for digit in decimals.iter_mut().rev() {
+            if !carry {
+                break;
+            }
+
+            *digit = (*digit + 1) % 10;
+            carry = *digit == 0;
+        }
+
+        let whole = match (carry, digits) {
+            (true, _) => "1",
+            (false, 0) => "0",
+            (false, _) => "",
+        };
+        let decimals: String = decimals
+            .iter()
+            .map(|&digit| char::from(b'0' + digit))
+            .collect();
+
+        if digits == 0 {
+            format!("{}{}", sign, whole)
+        } else {
+            format!("{}{}.{}", sign, whole, decimals)
         }
     }
+
+    /// The nearest word to `value` with the binary point `point` places below
+    /// the top of the magnitude; `None` if it doesn't fit once rounded.
+    #[must_use]
+    pub fn from_scaled(value: f64, point: u32) -> Option<Word> {
+        Word::from_f64(value / 2_f64.powi(point as i32))
+    }
+
+    /// The word read with the binary point `point` places below the top of the
+    /// magnitude, so `to_scaled(0)` is [`Word::to_f64`] and
+    /// `to_scaled(FRACTION_BITS)` is the word as an integer.
+    #[must_use]
+    pub fn to_scaled(self, point: u32) -> f64 {
+        self.to_f64() * 2_f64.powi(point as i32)
+    }
 }
 
 #[cfg(test)]
@@ -211,9 +432,10 @@ mod tests {
                 ),
             ),
             (
+                // the upper half is zero, so it's +0
                 (-1, 1),
                 (
-                    0b00000000000000000000000000000000000000000001,
+                    0b00000000000000000000000000000000000000000000,
                     0b00000000000000000000000000000000000000000011,
                 ),
             ),
@@ -238,7 +460,7 @@ mod tests {
 
     #[test]
     fn overflowing_div() {
-        let cases: [((Word, Word), (Word, Word, bool)); 2] = [
+        let cases: [((Word, Word), (Word, Word, bool)); 3] = [
             (
                 (
                     Word::from_bits(0b00110000000000000000000000000000000000000000),
@@ -261,10 +483,258 @@ mod tests {
                     false,
                 ),
             ),
+            (
+                // a negative quotient overflows too
+                ((-1).try_into().unwrap(), 1.try_into().unwrap()),
+                (
+                    Word::from_bits(U43_MAX << 1 | 0b1),
+                    Word::from_bits(U43_MAX << 1 | 0b1),
+                    true,
+                ),
+            ),
         ];
 
         for ((dividend, divisor), result) in cases {
             assert_eq!(dividend.overflowing_div(divisor), result);
         }
     }
+
+    #[test]
+    fn overflowing_add() {
+        let max: Word = (U43_MAX as i64).try_into().unwrap();
+        let one: Word = 1.try_into().unwrap();
+
+        let (sum, overflowed) = max.overflowing_add(one);
+        assert!(overflowed);
+        assert!(!sum.is_negative());
+
+        let (sum, overflowed) = (-max).overflowing_add(-one);
+        assert!(overflowed);
+        assert!(sum.is_negative());
+    }
+
+    #[test]
+    fn div_by_zero() {
+        let dividend: Word = 1.try_into().unwrap();
+        let divisor: Word = 0.try_into().unwrap();
+
+        assert!(dividend.overflowing_div(divisor).2);
+        // -0 is read as 0, so the quotient has the sign of the dividend
+        assert_eq!(
+            dividend.overflowing_div(-divisor),
+            dividend.overflowing_div(divisor)
+        );
+        assert!((-dividend).overflowing_div(-divisor).0.is_negative());
+    }
+
+    /// Words at the edges of the arithmetic, and ±0.
+    fn edge_words() -> Vec<Word> {
+        let mut words = vec![Word::from_bits(0), Word::from_bits(0b1)];
+        for magnitude in [
+            1,
+            2,
+            3,
+            1 << 21,
+            1 << 42,
+            (1 << 42) + 1,
+            U43_MAX - 1,
+            U43_MAX,
+        ] {
+            words.push(Word::from_bits(magnitude << 1));
+            words.push(Word::from_bits(magnitude << 1 | 0b1));
+        }
+
+        words
+    }
+
+    /// Every word a result is made of.
+    fn results_of(a: Word, b: Word) -> Vec<Word> {
+        let (sum, _) = a.overflowing_add(b);
+        let (difference, _) = a.overflowing_sub(b);
+        let (upper_product, lower_product) = a.mul(b);
+        let (upper_quotient, lower_quotient, _) = a.overflowing_div(b);
+
+        vec![
+            sum,
+            difference,
+            upper_product,
+            lower_product,
+            upper_quotient,
+            lower_quotient,
+        ]
+    }
+
+    #[test]
+    fn negative_zero() {
+        let negative_zero = Word::from_bits(0b1);
+        let zero = Word::from_bits(0);
+
+        // decoded as 0...
+        assert_eq!(i64::from(negative_zero), 0);
+        assert!(!negative_zero.is_negative());
+        assert!(negative_zero.is_sign_negative());
+        assert!(negative_zero.is_zero() && zero.is_zero());
+        assert_ne!(negative_zero, zero);
+
+        // ...but only made by working on the bits
+        assert_eq!(Word::try_from(0).unwrap(), zero);
+        assert_eq!(-zero, negative_zero);
+        let mut word = zero;
+        word.set_sign(true);
+        assert_eq!(word, negative_zero);
+
+        for a in edge_words() {
+            for b in edge_words() {
+                let results = results_of(a, b);
+
+                // zero is only encoded as +0
+                for word in &results {
+                    assert!(
+                        !(word.is_zero() && word.is_sign_negative()),
+                        "-0 from {a:?} and {b:?}: {results:?}"
+                    );
+                }
+
+                // and -0 works the same as +0 wherever it's read
+                let read = |word: Word| if word.is_zero() { zero } else { word };
+                assert_eq!(results, results_of(read(a), read(b)), "{a:?} and {b:?}");
+            }
+        }
+    }
+
+    /// The magnitude of a two-word result.
+    fn magnitude(upper: Word, lower: Word) -> u128 {
+        u128::from(upper.magnitude()) << 43 | u128::from(lower.magnitude())
+    }
+
+    #[test]
+    fn edge_values() {
+        for a in edge_words() {
+            for b in edge_words() {
+                let (x, y) = (i128::from(i64::from(a)), i128::from(i64::from(b)));
+
+                let (sum, overflowed) = a.overflowing_add(b);
+                assert_eq!(overflowed, (x + y).unsigned_abs() > u128::from(U43_MAX));
+                if !overflowed {
+                    assert_eq!(i128::from(i64::from(sum)), x + y);
+                }
+
+                let (upper, lower) = a.mul(b);
+                assert_eq!(magnitude(upper, lower), (x * y).unsigned_abs());
+                assert_eq!(upper.is_negative() || lower.is_negative(), x * y < 0);
+
+                let (upper, lower, overflowed) = a.overflowing_div(b);
+                assert_eq!(overflowed, y == 0 || x.abs() >= y.abs());
+                if !overflowed {
+                    // long division, a word at a time
+                    let (x, y) = (x.unsigned_abs() << 43, y.unsigned_abs());
+                    let expected = ((x / y) << 43) | (((x % y) << 43) / y);
+                    assert_eq!(magnitude(upper, lower), expected);
+                }
+                // overflowed or not, even over ±0
+                assert_eq!(upper.is_negative(), (x < 0) != (y < 0) && x != 0);
+            }
+        }
+    }
+
+    #[test]
+    fn fractions() {
+        // +1/10 and +3/80 round up in their last place, +5/8 is exact
+        let tenth = Word::from_bits(0o014_6314_6314_6315 << 1);
+        assert_eq!(Word::from_fraction(1, 10), Some(tenth));
+        assert_eq!(Word::from_f64(0.1), Some(tenth));
+        assert_eq!(Word::from_decimal("+.1"), Ok(tenth));
+        assert_eq!(Word::from_decimal("0.1000"), Ok(tenth));
+        assert_eq!(
+            Word::from_fraction(-3, 80),
+            Some(-Word::from_bits(0o004_6314_6314_6315 << 1))
+        );
+        let five_eighths = Word::from_bits(0o120_0000_0000_0000 << 1);
+        assert_eq!(Word::from_fraction(5, 8), Some(five_eighths));
+        assert_eq!(five_eighths.to_fraction(), (5, 8));
+        assert_eq!((-five_eighths).to_f64(), -0.625);
+
+        // halfway between two words rounds away from zero, and anything that
+        // rounds to zero is +0
+        let last_place = Word::from_bits(0b10);
+        assert_eq!(Word::from_fraction(1, 1 << 44), Some(last_place));
+        assert_eq!(Word::from_fraction(-1, 1 << 44), Some(-last_place));
+        assert_eq!(Word::from_fraction(-1, 1 << 45), Some(Word::from_bits(0)));
+        assert_eq!(Word::from_f64(-0.0), Some(Word::from_bits(0)));
+        assert_eq!(
+            Word::from_decimal("-.0000000000000000000000000000000000000000000001"),
+            Ok(Word::from_bits(0))
+        );
+
+        // nothing rounds to ±1
+        assert_eq!(Word::from_fraction(1, 1), None);
+        assert_eq!(Word::from_fraction((1 << 44) - 1, 1 << 44), None);
+        assert_eq!(Word::from_fraction(1, 0), None);
+        assert_eq!(Word::from_f64(-1.0), None);
+        assert_eq!(Word::from_f64(f64::NAN), None);
+        assert_eq!(
+            Word::from_decimal("-.99999999999999999"),
+            Err("Value is too large")
+        );
+        assert_eq!(Word::from_decimal("1.0"), Err("Value is too large"));
+        for text in ["", ".", "+", "1/2", ".5.", " .5", "0x.8"] {
+            assert_eq!(
+                Word::from_decimal(text),
+                Err("Not a decimal fraction"),
+                "{text}"
+            );
+        }
+
+        // every word comes back from its exact forms, -0 as +0
+        for word in edge_words() {
+            let expected = if word.is_zero() {
+                Word::from_bits(0)
+            } else {
+                word
+            };
+            let (numerator, denominator) = word.to_fraction();
+
+            assert_eq!(
+                Word::from_fraction(numerator, denominator as i64),
+                Some(expected)
+            );
+            assert_eq!(Word::from_f64(word.to_f64()), Some(expected));
+            assert_eq!(Word::from_decimal(&word.to_decimal(43)), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn decimals() {
+        let tenth = Word::from_fraction(1, 10).unwrap();
+        assert_eq!(tenth.to_decimal(13), "+.1000000000000");
+        assert_eq!(tenth.to_decimal(1), "+.1");
+        assert_eq!((-tenth).to_decimal(0), "-0");
+        assert_eq!(Word::from_bits(0b1).to_decimal(3), "+.000");
+        assert_eq!(Word::from_bits(0b1).to_decimal(0), "+0");
+
+        // rounding half up, carrying as far as it has to
+        let max = Word::from_bits(U43_MAX << 1);
+        assert_eq!(max.to_decimal(3), "+1.000");
+        assert_eq!(Word::from_fraction(1, 2).unwrap().to_decimal(0), "+1");
+        assert_eq!(Word::from_fraction(1, 8).unwrap().to_decimal(2), "+.13");
+        assert_eq!(Word::from_fraction(-1, 8).unwrap().to_decimal(3), "-.125");
+        // every word is exact in 43 digits
+        assert_eq!(
+            Word::from_bits(0b10).to_decimal(45),
+            "+.000000000000113686837721616029739379882812500"
+        );
+    }
+
+    #[test]
+    fn scaled() {
+        let thirteen: Word = 13.try_into().unwrap();
+        assert_eq!(Word::from_scaled(13.0, FRACTION_BITS), Some(thirteen));
+        assert_eq!(thirteen.to_scaled(FRACTION_BITS), 13.0);
+        assert_eq!(
+            Word::from_scaled(1.0, 10),
+            Some(Word::from_bits(0o000_1000_0000_0000 << 1))
+        );
+        assert_eq!(Word::from_scaled(-2.75, 2), Word::from_fraction(-11, 16));
+        assert_eq!(Word::from_scaled(8.0, 3), None);
+    }
 }
@@ -0,0 +1,647 @@
+//! # Headless runner
+//! Runs a program without the GUI, for use in scripts and CI. Programs are put
+//! on wires exactly as they are in the GUI, the console switches are set from
+//! the command line, and the final state of the machine is printed to standard
+//! output in a line-oriented format:
+//! ```text
+//! status halted
+//! halt_address 1034
+//! iar 1034
+//! orders 1234
+//! elapsed_micros 5678
+//! wire 1 reading 1200 recording 0 translating 0 rewinding 0 utilization 0.2113
+//! ...
+//! typed +000 0000 0000 0001
+//! ...
+//! memory 0000 000000000000000
+//! ...
+//! drum 00110 000000000000004
+//! ...
+//! cards read 2 punched 1
+//! ```
+//! Addresses are in octal and memory words are the raw 44 bits of the word, in
+//! octal. Each `wire` line gives how long the handler of that spool spent
+//! doing what, in microseconds, and the fraction of the run it was busy for
+//! (see [`edvac::transport`]; wires take no time unless `--wire-speeds1` and
+//! friends are given), and each `typed` line is a line of typewriter
+//! output (see [`edvac::typewriter`]). With `--drum`, every word on the drum
+//! that isn't blank gets a `drum` line giving its drum address (see
+//! [`edvac::drum`]). With `--cards`, the `cards` line gives how many cards the
+//! card unit read and punched (see [`edvac::cards`]). A faulted machine also
+//! gets a `fault` line describing the fault.
+//!
+//! `--trace` writes a trace of every order executed (see [`edvac::trace`]),
+//! which `edvac-trace-diff` can compare against another run.
+//!
+//! Long runs can be checkpointed with `--save-snapshot` and picked back up with
+//! `--load-snapshot`; any other options are applied on top of the snapshot.
+//! Wires can be saved on their own with `--save-wire1` and friends, in the
+//! format described in [`edvac::wire`], and loaded again like any other wire.
+
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    process,
+};
+
+use edvac::{
+    arithmetic::ArithmeticBackend,
+    assembler::{assemble, assemble_words},
+    cards::{CardDeck, CardFormat, CardUnit},
+    drum::Drum,
+    operating_console::{ExcessCapacityAction, MemoryMode},
+    timing::pulse_times_to_micros,
+    trace::TraceWriter,
+    transport::{TransportSpeeds, WireEndAction},
+    typewriter::TypewriterMode,
+    wire::{Wire, WireSpool},
+    word::Word,
+    Edvac, EdvacStatus,
+};
+
+const USAGE: &str = "\
+Usage: edvac-headless [OPTIONS]
+
+Options:
+    --wire1 FILE, --wire2 FILE, --wire3 FILE
+                            put a program listing (.edvac), a saved wire
+                            (.wire) or raw bytes onto a wire spool
+    --save-wire1 FILE, --save-wire2 FILE, --save-wire3 FILE
+                            save a wire spool once the machine stops
+    --wire-end1 ACTION, --wire-end2 ACTION, --wire-end3 ACTION
+                            what to do when a wire runs off either end: halt
+                            (fault the machine, the default) or stop
+    --wire-speeds1 SPEEDS, --wire-speeds2 SPEEDS, --wire-speeds3 SPEEDS
+                            how long a wire handler takes, in pulse times, as
+                            bit,rewind-bit,reversal (wires take no time by
+                            default)
+    --drum                  attach a magnetic drum, enabling drum transfer
+                            orders
+    --drum-word-time TIME   attach a drum that takes this many pulse times for
+                            a word to pass its head (the drum takes no time by
+                            default)
+    --arithmetic BACKEND    how arithmetic orders are worked out: native (the
+                            default) or serial
+    --check-arithmetic      check every arithmetic order against a second
+                            implementation, faulting if they disagree
+    --cards SPOOL           put a card reader and punch in place of a wire spool
+                            (0 to 3)
+    --card-format FORMAT    how words are punched on cards: octal (the
+                            default) or binary
+    --card-deck FILE        put a deck of cards, as text or as binary column
+                            images (.cbn), in the card hopper
+    --punch FILE            save the cards punched once the machine stops, as
+                            text or as binary column images (.cbn)
+    --typewriter MODE       how words recorded onto wire 0 are typed: octal
+                            (the default) or decimal
+    --iar ADDRESS           address to start executing from
+    --special ORDER         special order switches, e.g. \"W 0000 0301 0000 0000\"
+    --start-special         execute the special order before running, like
+                            pressing Initiate in a Special mode; the program
+                            then starts from the order's fourth address
+    --aux WORD              auxiliary input switches, e.g. \"+000 0000 0000 0001\"
+    --address-a ADDRESS     Address A switches
+    --address-b ADDRESS     Address B switches
+    --memory-mode MODE      l0, lr or r1
+    --excess-add ACTION     halt, ignore, special or address-b
+    --excess-div ACTION     halt, ignore, special or address-b
+    --max-orders N          stop after this many orders (default 1000000)
+    --load-snapshot FILE    start from a snapshot of the machine
+    --save-snapshot FILE    save a snapshot of the machine once it stops
+    --trace FILE            write a trace of every order executed
+    --help                  print this message
+
+Exits with 0 if the machine halted, 2 if it faulted and 3 if it ran out of
+orders.";
+
+struct Options {
+    wires: [Option<String>; 3],
+    save_wires: [Option<String>; 3],
+    cards: Option<WireSpool>,
+    card_format: CardFormat,
+    card_deck: Option<String>,
+    punch: Option<String>,
+    start_special: bool,
+    max_orders: usize,
+    save_snapshot: Option<String>,
+    trace: Option<String>,
+}
+
+fn parse_address(text: &str) -> Result<usize, String> {
+    match usize::from_str_radix(text, 8) {
+        Ok(address) if address < 1024 => Ok(address),
+        _ => Err(format!("`{}` is not an octal address", text)),
+    }
+}
+
+fn parse_word(text: &str) -> Result<Word, String> {
+    match assemble_words(&format!("0000 {}", text)).as_deref() {
+        Ok([(_, word)]) => Ok(*word),
+        _ => Err(format!("`{}` is not an order or number", text)),
+    }
+}
+
+fn parse_excess_capacity_action(text: &str) -> Result<ExcessCapacityAction, String> {
+    match text {
+        "halt" => Ok(ExcessCapacityAction::Halt),
+        "ignore" => Ok(ExcessCapacityAction::Ignore),
+        "special" => Ok(ExcessCapacityAction::ExecuteSpecial),
+        "address-b" => Ok(ExcessCapacityAction::ExecuteAddressB),
+        _ => Err(format!("`{}` is not an excess capacity action", text)),
+    }
+}
+
+fn parse_wire_end_action(text: &str) -> Result<WireEndAction, String> {
+    match text {
+        "halt" => Ok(WireEndAction::Halt),
+        "stop" => Ok(WireEndAction::Stop),
+        _ => Err(format!("`{}` is not a wire end action", text)),
+    }
+}
+
+fn parse_transport_speeds(text: &str) -> Result<TransportSpeeds, String> {
+    let times: Vec<u64> = text
+        .split(',')
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("`{}` is not a list of times", text))?;
+
+    match times[..] {
+        [bit_time, rewind_bit_time, reversal_time] => Ok(TransportSpeeds {
+            bit_time,
+            rewind_bit_time,
+            reversal_time,
+        }),
+        _ => Err(format!("`{}` is not three times", text)),
+    }
+}
+
+fn parse_typewriter_mode(text: &str) -> Result<TypewriterMode, String> {
+    match text {
+        "octal" => Ok(TypewriterMode::Octal),
+        "decimal" => Ok(TypewriterMode::DecimalFraction),
+        _ => Err(format!("`{}` is not a typewriter mode", text)),
+    }
+}
+
+fn parse_spool(text: &str) -> Result<WireSpool, String> {
+    text.parse()
+        .ok()
+        .and_then(|spool: u64| WireSpool::try_from(spool).ok())
+        .ok_or_else(|| format!("`{}` is not a wire spool", text))
+}
+
+fn parse_arithmetic_backend(text: &str) -> Result<ArithmeticBackend, String> {
+    match text {
+        "native" => Ok(ArithmeticBackend::Native),
+        "serial" => Ok(ArithmeticBackend::Serial),
+        _ => Err(format!("`{}` is not an arithmetic backend", text)),
+    }
+}
+
+fn parse_card_format(text: &str) -> Result<CardFormat, String> {
+    match text {
+        "octal" => Ok(CardFormat::Octal),
+        "binary" => Ok(CardFormat::ColumnBinary),
+        _ => Err(format!("`{}` is not a card format", text)),
+    }
+}
+
+fn parse_memory_mode(text: &str) -> Result<MemoryMode, String> {
+    match text {
+        "l0" => Ok(MemoryMode::L0),
+        "lr" => Ok(MemoryMode::LR),
+        "r1" => Ok(MemoryMode::R1),
+        _ => Err(format!("`{}` is not a memory mode", text)),
+    }
+}
+
+/// Sets up the console switches of `computer` from the arguments, returning
+/// everything else.
+fn parse_args(computer: &mut Edvac, args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        wires: [None, None, None],
+        save_wires: [None, None, None],
+        cards: None,
+        card_format: CardFormat::default(),
+        card_deck: None,
+        punch: None,
+        start_special: false,
+        max_orders: 1_000_000,
+        save_snapshot: None,
+        trace: None,
+    };
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        if flag == "--start-special" {
+            options.start_special = true;
+            continue;
+        }
+        if flag == "--drum" {
+            computer.drum.get_or_insert_with(Drum::default);
+            continue;
+        }
+        if flag == "--check-arithmetic" {
+            computer.arithmetic_check = true;
+            continue;
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| format!("`{}` needs a value", flag))?;
+
+        let state = &mut computer.state;
+        match flag.as_str() {
+            "--wire1" => options.wires[0] = Some(value.clone()),
+            "--wire2" => options.wires[1] = Some(value.clone()),
+            "--wire3" => options.wires[2] = Some(value.clone()),
+            "--save-wire1" => options.save_wires[0] = Some(value.clone()),
+            "--save-wire2" => options.save_wires[1] = Some(value.clone()),
+            "--save-wire3" => options.save_wires[2] = Some(value.clone()),
+            "--wire-end1" => computer.wire_transports[0].end_action = parse_wire_end_action(value)?,
+            "--wire-end2" => computer.wire_transports[1].end_action = parse_wire_end_action(value)?,
+            "--wire-end3" => computer.wire_transports[2].end_action = parse_wire_end_action(value)?,
+            "--wire-speeds1" => computer.wire_transports[0].speeds = parse_transport_speeds(value)?,
+            "--wire-speeds2" => computer.wire_transports[1].speeds = parse_transport_speeds(value)?,
+            "--wire-speeds3" => computer.wire_transports[2].speeds = parse_transport_speeds(value)?,
+            "--drum-word-time" => {
+                computer.drum.get_or_insert_with(Drum::default).word_time = value
+                    .parse()
+                    .map_err(|_| format!("`{}` is not a number", value))?;
+            }
+            "--cards" => options.cards = Some(parse_spool(value)?),
+            "--arithmetic" => computer.arithmetic_backend = parse_arithmetic_backend(value)?,
+            "--card-format" => options.card_format = parse_card_format(value)?,
+            "--card-deck" => options.card_deck = Some(value.clone()),
+            "--punch" => options.punch = Some(value.clone()),
+            "--typewriter" => computer.typewriter.mode = parse_typewriter_mode(value)?,
+            "--iar" => {
+                computer.status = EdvacStatus::Halted {
+                    resume_addr: parse_address(value)?,
+                }
+            }
+            "--special" => state.special_order_switches = parse_word(value)?,
+            "--aux" => state.auxiliary_input_switches = parse_word(value)?,
+            "--address-a" => state.address_a_switches = parse_address(value)?,
+            "--address-b" => state.address_b_switches = parse_address(value)?,
+            "--memory-mode" => state.memory_mode = parse_memory_mode(value)?,
+            "--excess-add" => {
+                state.excess_capacity_action_add = parse_excess_capacity_action(value)?
+            }
+            "--excess-div" => {
+                state.excess_capacity_action_div = parse_excess_capacity_action(value)?
+            }
+            "--max-orders" => {
+                options.max_orders = value
+                    .parse()
+                    .map_err(|_| format!("`{}` is not a number", value))?;
+            }
+            "--save-snapshot" => options.save_snapshot = Some(value.clone()),
+            "--trace" => options.trace = Some(value.clone()),
+            // already loaded by `run`
+            "--load-snapshot" => {}
+            _ => return Err(format!("unknown option `{}`", flag)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn load_wire(path: &str) -> Result<Wire, String> {
+    let mut wire = if path.ends_with(".edvac") {
+        let listing = fs::read_to_string(path).map_err(|error| format!("{}: {}", path, error))?;
+
+        assemble(&listing).map_err(|errors| {
+            errors
+                .iter()
+                .map(|error| format!("{}:{}", path, error))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })?
+    } else if path.ends_with(".wire") {
+        File::open(path)
+            .and_then(|file| Wire::load(BufReader::new(file)))
+            .map_err(|error| format!("{}: {}", path, error))?
+    } else {
+        fs::read(path)
+            .and_then(Wire::with_bytes)
+            .map_err(|error| format!("{}: {}", path, error))?
+    };
+
+    // saved wires keep the label they were saved with
+    if wire.label().is_empty() {
+        wire.set_label(path);
+    }
+
+    Ok(wire)
+}
+
+fn load_deck(path: &str) -> Result<CardDeck, String> {
+    if path.ends_with(".cbn") {
+        File::open(path)
+            .and_then(|file| CardDeck::load(BufReader::new(file)))
+            .map_err(|error| format!("{}: {}", path, error))
+    } else {
+        fs::read_to_string(path)
+            .map_err(|error| error.to_string())
+            .and_then(|text| CardDeck::parse(&text).map_err(|error| error.to_string()))
+            .map_err(|error| format!("{}: {}", path, error))
+    }
+}
+
+fn save_deck(deck: &CardDeck, path: &str) -> Result<(), String> {
+    if path.ends_with(".cbn") {
+        File::create(path)
+            .and_then(|file| deck.save(BufWriter::new(file)))
+            .map_err(|error| format!("{}: {}", path, error))
+    } else {
+        deck.to_text()
+            .map_err(|error| error.to_string())
+            .and_then(|text| fs::write(path, text).map_err(|error| error.to_string()))
+            .map_err(|error| format!("{}: {}", path, error))
+    }
+}
+
+fn save_wire(wire: &Wire, path: &str) -> Result<(), String> {
+    File::create(path)
+        .and_then(|file| wire.save(BufWriter::new(file)))
+        .map_err(|error| format!("{}: {}", path, error))
+}
+
+fn print_state(out: &mut impl Write, computer: &Edvac, orders: usize) -> io::Result<()> {
+    match computer.status {
+        EdvacStatus::Running => writeln!(out, "status running")?,
+        EdvacStatus::Halted { resume_addr } => {
+            writeln!(out, "status halted")?;
+            writeln!(out, "halt_address {:0>4o}", resume_addr)?;
+        }
+        EdvacStatus::Faulted { resume_addr, error } => {
+            writeln!(out, "status faulted")?;
+            writeln!(out, "halt_address {:0>4o}", resume_addr)?;
+            writeln!(out, "fault {}", error)?;
+        }
+    }
+
+    writeln!(out, "iar {:0>4o}", computer.state.initial_address_register)?;
+    writeln!(out, "orders {}", orders)?;
+    writeln!(out, "elapsed_micros {}", computer.elapsed_micros())?;
+
+    for (spool, transport) in computer.wire_transports.iter().enumerate() {
+        let usage = transport.usage();
+
+        writeln!(
+            out,
+            "wire {} reading {} recording {} translating {} rewinding {} utilization {:.4}",
+            spool + 1,
+            pulse_times_to_micros(usage.reading),
+            pulse_times_to_micros(usage.recording),
+            pulse_times_to_micros(usage.translating),
+            pulse_times_to_micros(usage.rewinding),
+            transport.utilization(&computer.clock),
+        )?;
+    }
+
+    for line in computer.typewriter.output().lines() {
+        writeln!(out, "typed {}", line)?;
+    }
+
+    for (address, word) in computer.high_speed_memory.dump().iter().enumerate() {
+        writeln!(out, "memory {:0>4o} {:0>15o}", address, word.get_bits())?;
+    }
+
+    if let Some(unit) = &computer.card_unit {
+        writeln!(
+            out,
+            "cards read {} punched {}",
+            unit.cards_read(),
+            unit.punched().cards().len()
+        )?;
+    }
+
+    if let Some(drum) = &computer.drum {
+        for (address, word) in drum.dump().iter().enumerate() {
+            if word.get_bits() != 0 {
+                writeln!(out, "drum {:0>5o} {:0>15o}", address, word.get_bits())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn load_snapshot(path: &str) -> Result<Edvac, String> {
+    File::open(path)
+        .map_err(|error| error.into())
+        .and_then(|file| Edvac::load_snapshot(BufReader::new(file)))
+        .map_err(|error| format!("{}: {}", path, error))
+}
+
+fn save_snapshot(computer: &Edvac, path: &str) -> Result<(), String> {
+    File::create(path)
+        .map_err(|error| error.into())
+        .and_then(|file| computer.save_snapshot(BufWriter::new(file)))
+        .map_err(|error| format!("{}: {}", path, error))
+}
+
+/// Runs the machine as the arguments describe, printing its final state to
+/// `out` and returning the exit code.
+fn run(args: &[String], out: &mut impl Write) -> Result<i32, String> {
+    let snapshot = args
+        .iter()
+        .position(|arg| arg == "--load-snapshot")
+        .and_then(|index| args.get(index + 1));
+
+    let mut computer = match snapshot {
+        Some(path) => load_snapshot(path)?,
+        None => Edvac::default(),
+    };
+    let options = parse_args(&mut computer, args)?;
+
+    for (spool, path) in options.wires.iter().enumerate() {
+        if let Some(path) = path {
+            computer.low_speed_memory[spool] = load_wire(path)?;
+        }
+    }
+
+    if let Some(spool) = options.cards {
+        computer.card_unit = Some(CardUnit::new(spool, options.card_format));
+    }
+    if let Some(path) = &options.card_deck {
+        let deck = load_deck(path)?;
+        computer
+            .card_unit
+            .as_mut()
+            .ok_or("`--card-deck` needs `--cards`")?
+            .load_hopper(deck);
+    }
+
+    if let Some(path) = &options.trace {
+        let file = File::create(path).map_err(|error| format!("{}: {}", path, error))?;
+        computer.attach_observer(Box::new(TraceWriter::new(BufWriter::new(file))));
+    }
+
+    if options.start_special {
+        computer.initiate_pressed();
+        // faults are reported along with the rest of the state
+        let _ = computer.execute_special_order();
+    }
+
+    let mut orders = 0;
+    if !matches!(computer.status, EdvacStatus::Faulted { .. }) {
+        computer.initiate_pressed();
+
+        // this is `continue_to_completion`, with a limit
+        while computer.status == EdvacStatus::Running && orders < options.max_orders {
+            orders += 1;
+
+            if computer.step_once().is_err() {
+                break;
+            }
+        }
+    }
+
+    // flushes the trace
+    drop(computer.detach_observers());
+
+    print_state(out, &computer, orders).map_err(|error| error.to_string())?;
+
+    if let Some(path) = &options.save_snapshot {
+        save_snapshot(&computer, path)?;
+    }
+
+    if let (Some(unit), Some(path)) = (&computer.card_unit, &options.punch) {
+        save_deck(unit.punched(), path)?;
+    }
+
+    for (wire, path) in computer.low_speed_memory.iter().zip(&options.save_wires) {
+        if let Some(path) = path {
+            save_wire(wire, path)?;
+        }
+    }
+
+    Ok(match computer.status {
+        EdvacStatus::Halted { .. } => 0,
+        EdvacStatus::Faulted { .. } => 2,
+        EdvacStatus::Running => 3,
+    })
+}
+
+pub fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.iter().any(|arg| arg == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+
+    match run(&args, &mut io::stdout().lock()) {
+        Ok(code) => process::exit(code),
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_switches() {
+        let args: Vec<String> = [
+            "--iar",
+            "1003",
+            "--excess-add",
+            "ignore",
+            "--max-orders",
+            "10",
+            "--wire-speeds2",
+            "100,20,20000",
+            "--drum-word-time",
+            "232",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+
+        let mut computer = Edvac::default();
+        let options = parse_args(&mut computer, &args).unwrap();
+        assert_eq!(options.max_orders, 10);
+        assert_eq!(
+            computer.status,
+            EdvacStatus::Halted {
+                resume_addr: 0o1003
+            }
+        );
+        assert_eq!(
+            computer.state.excess_capacity_action_add,
+            ExcessCapacityAction::Ignore
+        );
+        assert_eq!(
+            computer.wire_transports[1].speeds,
+            TransportSpeeds {
+                bit_time: 100,
+                rewind_bit_time: 20,
+                reversal_time: 20_000,
+            }
+        );
+        assert_eq!(computer.drum.unwrap().word_time, 232);
+
+        let mut computer = Edvac::default();
+        for args in [
+            &["--iar", "2000"][..],
+            &["--iar"],
+            &["--bogus", "1"],
+            &["--wire-speeds1", "1,2"],
+        ] {
+            let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            assert!(parse_args(&mut computer, &args).is_err());
+        }
+    }
+
+    #[test]
+    fn runs_dec_to_bin() {
+        let listing = include_str!("test_programs/dec_to_bin.edvac");
+
+        let mut computer = Edvac::default();
+        computer
+            .high_speed_memory
+            .load(assemble_words(listing).unwrap())
+            .unwrap();
+        // the digit count at 1001 isn't part of the listing
+        computer
+            .high_speed_memory
+            .load(vec![(0o1001, (1 << 13).try_into().unwrap())])
+            .unwrap();
+        computer.low_speed_memory[1].write_word(Word::from_bits(
+            0b0010_0010_0010_0010_0010_0010_0010_0010_0010_0010_0000,
+        ));
+
+        let path = env::temp_dir().join(format!("edvac-headless-{}.snapshot", process::id()));
+        save_snapshot(&computer, path.to_str().unwrap()).unwrap();
+
+        let mut out = Vec::new();
+        let args = ["--load-snapshot".to_string(), path.display().to_string()];
+        let code = run(&args, &mut out).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(code, 0);
+        assert!(out.starts_with("status halted\nhalt_address 0000\n"));
+        assert!(out.contains(&format!(
+            "memory 0001 {:0>15o}\n",
+            0b00111000111000111000111000111000110010110000_u64
+        )));
+
+        // running out of orders is reported too
+        let mut out = Vec::new();
+        let args = ["--max-orders".to_string(), "0".to_string()];
+        assert_eq!(run(&args, &mut out).unwrap(), 3);
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .starts_with("status running\n"));
+    }
+}
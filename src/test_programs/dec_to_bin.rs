@@ -2,7 +2,7 @@
 
 use crate::{word::Word, Edvac};
 
-fn load(computer: &mut Edvac) {
+pub(crate) fn load(computer: &mut Edvac) {
     computer.high_speed_memory.load(vec![
         (0o0000, inst!(E 0o1001 0o0003 0o1003 0o1003)),
         (0o1003, inst!(W 0o0001 0o0202 0o0000 0o1002)),
@@ -52,7 +52,8 @@ fn load(computer: &mut Edvac) {
         (0o1035, Word::from_bits(0o120_0000_0000_0000 << 1)), // +5/8
         (0o1042, Word::from_bits(0o000_1000_0000_0000 << 1)), // +2^-10
         (0o1001, (1 << 13).try_into().unwrap()),
-    ]);
+    ])
+    .unwrap();
 }
 
 #[test]
@@ -66,10 +67,25 @@ fn works() {
     ));
 
     computer.initiate_pressed();
-    computer.continue_to_completion();
+    computer.continue_to_completion().unwrap();
 
     assert_eq!(
         computer.high_speed_memory.dump()[0o0001],
         Word::from_bits(0b00111000111000111000111000111000110010110000)
     );
 }
+
+#[test]
+fn constants() {
+    // the program's constants are the nearest words to the fractions noted
+    let memory = {
+        let mut computer = Edvac::default();
+        load(&mut computer);
+        computer.high_speed_memory.dump()
+    };
+
+    assert_eq!(Word::from_fraction(1, 10), Some(memory[0o1016]));
+    assert_eq!(Word::from_fraction(3, 80), Some(memory[0o1026]));
+    assert_eq!(Word::from_fraction(5, 8), Some(memory[0o1035]));
+    assert_eq!(Word::from_fraction(1, 1 << 10), Some(memory[0o1042]));
+}
@@ -0,0 +1,88 @@
+//! # Trace comparator
+//! Compares two traces written by `edvac-headless --trace` (see
+//! [`edvac::trace`]) and reports the first order at which they differ, along
+//! with the orders leading up to it:
+//! ```text
+//! traces diverge at order 1234
+//!   1045 S 1006 1042 1006 1037 ...
+//! - 1037 C 1006 1042 1055 1006 ...
+//! + 1037 C 1006 1042 1055 1006 ...
+//! ```
+
+use std::{env, fs::File, io::BufReader, process};
+
+use edvac::trace::{first_divergence, read_trace, TraceRecord};
+
+const USAGE: &str = "\
+Usage: edvac-trace-diff [--context N] A B
+
+Prints the first order at which traces A and B differ, preceded by N orders of
+context (default 5). Exits with 0 if the traces are the same, 1 if they differ
+and 2 if something went wrong.";
+
+fn load(path: &str) -> Result<Vec<TraceRecord>, String> {
+    File::open(path)
+        .map_err(|error| error.into())
+        .and_then(|file| read_trace(BufReader::new(file)))
+        .map_err(|error| format!("{}: {}", path, error))
+}
+
+fn run(args: &[String]) -> Result<bool, String> {
+    let (context, paths) = match args {
+        [flag, context, paths @ ..] if flag == "--context" => (
+            context
+                .parse()
+                .map_err(|_| format!("`{}` is not a number", context))?,
+            paths,
+        ),
+        paths => (5, paths),
+    };
+
+    let (a, b) = match paths {
+        [a, b] => (load(a)?, load(b)?),
+        _ => return Err("expected two traces".to_string()),
+    };
+
+    let index = match first_divergence(&a, &b) {
+        Some(index) => index,
+        None => {
+            println!("traces are the same ({} orders)", a.len());
+            return Ok(true);
+        }
+    };
+
+    println!("traces diverge at order {}", index + 1);
+
+    for record in &a[index.saturating_sub(context)..index] {
+        println!("  {}", record);
+    }
+
+    match a.get(index) {
+        Some(record) => println!("- {}", record),
+        None => println!("- (end of trace)"),
+    }
+    match b.get(index) {
+        Some(record) => println!("+ {}", record),
+        None => println!("+ (end of trace)"),
+    }
+
+    Ok(false)
+}
+
+pub fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.iter().any(|arg| arg == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+
+    match run(&args) {
+        Ok(true) => process::exit(0),
+        Ok(false) => process::exit(1),
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    }
+}
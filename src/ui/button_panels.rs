@@ -7,6 +7,7 @@ pub struct OperationButtons {
     initiate_button: button::State,
     halt_button: button::State,
     read_out_button: button::State,
+    step_back_button: button::State,
 }
 
 #[derive(Debug, Clone)]
@@ -15,6 +16,7 @@ pub enum Message {
     Initiate,
     Halt,
     ReadOut,
+    StepBack,
 }
 
 impl OperationButtons {
@@ -24,6 +26,7 @@ impl OperationButtons {
             initiate_button: button::State::default(),
             halt_button: button::State::default(),
             read_out_button: button::State::default(),
+            step_back_button: button::State::default(),
         }
     }
 
@@ -79,6 +82,17 @@ impl OperationButtons {
                                     .on_press(Message::ReadOut)
                                     .style(ButtonStyle),
                             ),
+                    )
+                    .push(
+                        Column::new()
+                            .align_items(Align::Center)
+                            .push(Text::new("STEP BACK").size(16))
+                            .push(
+                                Button::new(&mut self.step_back_button, Text::new(""))
+                                    .padding(20)
+                                    .on_press(Message::StepBack)
+                                    .style(ButtonStyle),
+                            ),
                     ),
             )
             .push(Text::new("OPERATION").size(text::SIZE_LARGE))
@@ -9,3 +9,4 @@ pub mod program_loader;
 pub mod special_order_input;
 pub mod style;
 pub mod threading;
+pub mod typewriter_output;
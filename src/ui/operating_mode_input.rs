@@ -40,13 +40,17 @@ impl OperatingModeInput {
         match message {
             Message::Input(normal) => {
                 let (mode, position, is_normal) = match (normal.scale(7.0) as u8, self.is_normal) {
-                    (0..=5, false) => (OperatingMode::SpecialOneOrder, 5.0, false),
-                    (5.., false) => (OperatingMode::NormalToCompletion, 1.0, true),
+                    (3, false) => (OperatingMode::SpecialOneCycle, 3.0, false),
+                    (4, false) => (OperatingMode::SpecialOneExecute, 4.0, false),
+                    (0..=2 | 5, false) => (OperatingMode::SpecialOneOrder, 5.0, false),
+                    (6.., false) => (OperatingMode::NormalToCompletion, 1.0, true),
 
                     (0, true) => (OperatingMode::SpecialOneOrder, 5.0, false),
                     (1, true) => (OperatingMode::NormalToCompletion, 1.0, true),
                     (2, true) => (OperatingMode::NormalToAddressA, 2.0, true),
-                    (3.., true) => (OperatingMode::NormalOneOrder, 5.0, true),
+                    (3, true) => (OperatingMode::NormalOneCycle, 3.0, true),
+                    (4, true) => (OperatingMode::NormalOneExecute, 4.0, true),
+                    (5.., true) => (OperatingMode::NormalOneOrder, 5.0, true),
                 };
 
                 // snap the visible position while still allowing the knob to be
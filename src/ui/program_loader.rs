@@ -1,4 +1,8 @@
-use std::fs;
+use std::{
+    fs::{self, File},
+    io::BufReader,
+    path::PathBuf,
+};
 
 use iced::{button, Button, Column, Element, Radio, Text};
 use rfd::FileDialog;
@@ -12,6 +16,8 @@ use super::style::text;
 pub struct ProgramLoader {
     button_a: button::State,
     button_b: button::State,
+    button_c: button::State,
+    button_d: button::State,
 
     state: State,
 }
@@ -20,6 +26,7 @@ enum State {
     Ready,
     Message(String),
     Loaded(Wire),
+    Saving,
 }
 
 #[derive(Debug, Clone)]
@@ -27,20 +34,32 @@ pub enum Message {
     Dismiss,
     ProgramSelection,
     DataSelection,
+    WireFileSelection,
+    SaveSelection,
     WireSelected(WireSpool),
 }
 
+/// What the loader wants done with the machine's wires.
+pub enum Action {
+    Load(WireSpool, Wire),
+    /// Save the wire on the spool to a file. Only the core thread has the
+    /// wire, so it does the saving.
+    Save(WireSpool, PathBuf),
+}
+
 impl ProgramLoader {
     pub fn new() -> ProgramLoader {
         ProgramLoader {
             button_a: button::State::default(),
             button_b: button::State::default(),
+            button_c: button::State::default(),
+            button_d: button::State::default(),
 
             state: State::Ready,
         }
     }
 
-    pub fn update(&mut self, message: Message) -> Option<(WireSpool, Wire)> {
+    pub fn update(&mut self, message: Message) -> Option<Action> {
         match message {
             Message::Dismiss => {
                 self.state = State::Ready;
@@ -53,11 +72,18 @@ impl ProgramLoader {
                     .pick_file();
 
                 if let Some(path) = selection {
-                    if let Ok(listing) = fs::read_to_string(path) {
-                        if let Some(wire) = assemble(&listing) {
-                            self.state = State::Loaded(wire);
-                        } else {
-                            self.state = State::Message("Unable to assemble file.".into());
+                    if let Ok(listing) = fs::read_to_string(&path) {
+                        match assemble(&listing) {
+                            Ok(mut wire) => {
+                                wire.set_label(path.to_string_lossy());
+                                self.state = State::Loaded(wire);
+                            }
+                            Err(errors) => {
+                                self.state = State::Message(errors.iter().fold(
+                                    "Unable to assemble file:".to_string(),
+                                    |message, error| format!("{}\n{}", message, error),
+                                ));
+                            }
                         }
                     } else {
                         self.state = State::Message("Unable to open file.".into());
@@ -72,28 +98,59 @@ impl ProgramLoader {
                     .pick_file();
 
                 if let Some(path) = selection {
-                    if let Ok(bytes) = fs::read(path) {
-                        self.state = State::Loaded(Wire::with_bytes(bytes));
+                    if let Ok(bytes) = fs::read(&path) {
+                        match Wire::with_bytes(bytes) {
+                            Ok(mut wire) => {
+                                wire.set_label(path.to_string_lossy());
+                                self.state = State::Loaded(wire);
+                            }
+                            Err(error) => {
+                                self.state =
+                                    State::Message(format!("Unable to load wire:\n{}", error));
+                            }
+                        }
+                    } else {
+                        self.state = State::Message("Unable to open file.".into());
+                    }
+                }
+
+                None
+            }
+            Message::WireFileSelection => {
+                let selection = FileDialog::new()
+                    .add_filter("EDVAC wire", &["wire"])
+                    .pick_file();
+
+                if let Some(path) = selection {
+                    match File::open(path).and_then(|file| Wire::load(BufReader::new(file))) {
+                        Ok(wire) => self.state = State::Loaded(wire),
+                        Err(error) => {
+                            self.state = State::Message(format!("Unable to load wire:\n{}", error));
+                        }
                     }
                 }
 
                 None
             }
+            Message::SaveSelection => {
+                self.state = State::Saving;
+
+                None
+            }
             Message::WireSelected(spool) => {
-                let result = if let State::Loaded(wire) = &self.state {
-                    Some((spool, wire.clone()))
-                } else {
-                    None
-                };
-
-                if result.is_some() {
-                    self.state = State::Message("File loaded successfully".into());
-                } else {
+                match std::mem::replace(&mut self.state, State::Ready) {
+                    State::Loaded(wire) => {
+                        self.state = State::Message("File loaded successfully".into());
+
+                        Some(Action::Load(spool, wire))
+                    }
+                    State::Saving => FileDialog::new()
+                        .add_filter("EDVAC wire", &["wire"])
+                        .save_file()
+                        .map(|path| Action::Save(spool, path)),
                     // probably unreachable, fail-safe
-                    self.state = State::Ready;
+                    _ => None,
                 }
-
-                result
             }
         }
     }
@@ -115,6 +172,20 @@ impl ProgramLoader {
                     )
                     .on_press(Message::DataSelection),
                 )
+                .push(
+                    Button::new(
+                        &mut self.button_c,
+                        Text::new("Load Wire").size(text::SIZE_MEDIUM),
+                    )
+                    .on_press(Message::WireFileSelection),
+                )
+                .push(
+                    Button::new(
+                        &mut self.button_d,
+                        Text::new("Save Wire").size(text::SIZE_MEDIUM),
+                    )
+                    .on_press(Message::SaveSelection),
+                )
                 .into(),
             State::Message(text) => Column::new()
                 .push(Text::new(text.clone()).size(text::SIZE_MEDIUM))
@@ -126,11 +197,17 @@ impl ProgramLoader {
                     .on_press(Message::Dismiss),
                 )
                 .into(),
-            State::Loaded(_) => [WireSpool::One, WireSpool::Two, WireSpool::Three]
+            State::Loaded(_) | State::Saving => [WireSpool::One, WireSpool::Two, WireSpool::Three]
                 .iter()
                 .fold(
-                    Column::new()
-                        .push(Text::new("Select a wire to load into").size(text::SIZE_MEDIUM)),
+                    Column::new().push(
+                        Text::new(if let State::Saving = self.state {
+                            "Select a wire to save"
+                        } else {
+                            "Select a wire to load into"
+                        })
+                        .size(text::SIZE_MEDIUM),
+                    ),
                     |column, &variant| {
                         column.push(
                             Radio::new(
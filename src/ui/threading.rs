@@ -1,8 +1,17 @@
 //! # Allows EDVAC to run in its own thread
-use std::thread;
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use log::error;
 
 use edvac::{
     operating_console::{ExcessCapacityAction, MemoryMode, OperatingMode},
+    typewriter::TypewriterMode,
     wire::{Wire, WireSpool},
     word::Word,
     EdvacStatus,
@@ -60,32 +69,64 @@ pub enum StateParameter {
     AddressB(usize),
 }
 
+/// How many steps the Step Back button can undo.
+const UNDO_STEPS: usize = 10_000;
+
 pub enum EdvacMessage {
     Clear,
     Initiate,
     Halt,
+    StepBack,
 
     ModifyState(StateParameter),
 
     LoadWire(WireSpool, Wire),
+    SaveWire(WireSpool, PathBuf),
+
+    TypewriterMode(TypewriterMode),
+    ClearTypewriter,
 }
 
 pub struct EdvacThread {
     channel: bidi_channel::BidiChannel<EdvacMessage>,
+
+    /// A copy of everything typed so far, kept up to date by the core thread.
+    typewriter_output: Arc<Mutex<String>>,
+}
+
+/// Brings the copy of the typewriter output up to date. The core only ever adds
+/// to its output, or cuts it back when stepping back or clearing, so only the
+/// new end has to be copied over.
+fn sync_typewriter(computer: &Edvac, copy: &Mutex<String>) {
+    let output = computer.typewriter.output();
+    let mut copy = copy.lock().unwrap();
+
+    if output.len() < copy.len() {
+        copy.truncate(output.len());
+    } else if output.len() > copy.len() {
+        let typed = &output[copy.len()..];
+        copy.push_str(typed);
+    }
 }
 
 impl EdvacThread {
     pub fn new() -> EdvacThread {
         let (core_link, ui_link) = bidi_channel::channel_pair();
+        let typewriter_output = Arc::new(Mutex::new(String::new()));
+        let core_typewriter_output = Arc::clone(&typewriter_output);
 
         thread::spawn(move || {
             let mut computer = Edvac::default();
+            computer.journal.set_capacity(UNDO_STEPS);
 
             fn handle_message(computer: &mut Edvac, message: EdvacMessage) {
                 match message {
                     EdvacMessage::Clear => computer.clear_pressed(),
                     EdvacMessage::Initiate => computer.initiate_pressed(),
                     EdvacMessage::Halt => computer.halt_pressed(),
+                    EdvacMessage::StepBack => {
+                        computer.step_back();
+                    }
                     EdvacMessage::ModifyState(parameter) => match parameter {
                         StateParameter::OperatingMode(mode) => {
                             computer.state.operating_mode = mode;
@@ -113,6 +154,17 @@ impl EdvacThread {
                     EdvacMessage::LoadWire(spool, wire) => {
                         computer.low_speed_memory[usize::try_from(spool).unwrap()] = wire;
                     }
+                    EdvacMessage::SaveWire(spool, path) => {
+                        let wire = &computer.low_speed_memory[usize::try_from(spool).unwrap()];
+
+                        if let Err(error) =
+                            File::create(&path).and_then(|file| wire.save(BufWriter::new(file)))
+                        {
+                            error!("Unable to save wire to {}: {}", path.display(), error);
+                        }
+                    }
+                    EdvacMessage::TypewriterMode(mode) => computer.typewriter.mode = mode,
+                    EdvacMessage::ClearTypewriter => computer.typewriter.clear(),
                 };
             }
 
@@ -124,7 +176,11 @@ impl EdvacThread {
                         Err(_) => todo!(),
                     };
 
-                    match computer.state.operating_mode {
+                    // faults halt the machine by themselves, so there is
+                    // nothing more to do with them here
+                    let _ = match computer.state.operating_mode {
+                        OperatingMode::SpecialOneCycle => computer.one_cycle(true),
+                        OperatingMode::SpecialOneExecute => computer.one_execute(true),
                         OperatingMode::SpecialOneOrder => computer.execute_special_order(),
                         OperatingMode::NormalToCompletion => computer.step_once(),
                         OperatingMode::NormalToAddressA => {
@@ -132,30 +188,47 @@ impl EdvacThread {
                                 == computer.state.address_a_switches
                             {
                                 computer.halt_pressed();
+
+                                Ok(())
                             } else {
-                                computer.step_once();
+                                computer.step_once()
                             }
                         }
-                        OperatingMode::NormalOneOrder => {
-                            computer.step_once();
+                        OperatingMode::NormalOneCycle => computer.one_cycle(false),
+                        OperatingMode::NormalOneExecute => computer.one_execute(false),
+                        OperatingMode::NormalOneOrder => computer.step_once().map(|()| {
                             computer.halt_pressed();
-                        }
-                    }
+                        }),
+                    };
                 } else {
                     match core_link.recv() {
                         Ok(message) => handle_message(&mut computer, message),
                         Err(_) => todo!(),
                     }
                 }
+
+                sync_typewriter(&computer, &core_typewriter_output);
             }
         });
 
-        EdvacThread { channel: ui_link }
+        EdvacThread {
+            channel: ui_link,
+            typewriter_output,
+        }
     }
 
     pub fn send(&mut self, message: EdvacMessage) {
         self.channel.send(message).ok().unwrap();
     }
+
+    #[must_use]
+    pub fn typewriter_output(&self) -> String {
+        self.typewriter_output.lock().unwrap().clone()
+    }
+
+    pub fn clear_typewriter(&mut self) {
+        self.send(EdvacMessage::ClearTypewriter);
+    }
 }
 
 impl Default for EdvacThread {
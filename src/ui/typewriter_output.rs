@@ -0,0 +1,82 @@
+use edvac::typewriter::TypewriterMode;
+
+use iced::{button, scrollable, Button, Column, Element, Length, Radio, Row, Scrollable, Text};
+
+use super::style::text;
+
+pub struct TypewriterOutput {
+    clear_button: button::State,
+    scroll: scrollable::State,
+
+    mode: TypewriterMode,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Message {
+    Mode(TypewriterMode),
+    Clear,
+}
+
+impl TypewriterOutput {
+    pub fn new() -> TypewriterOutput {
+        TypewriterOutput {
+            clear_button: button::State::default(),
+            scroll: scrollable::State::default(),
+
+            mode: TypewriterMode::default(),
+        }
+    }
+
+    // clearing is handled by the App logic, since the output lives with the
+    // core thread
+    pub fn update(&mut self, message: Message) -> Option<TypewriterMode> {
+        match message {
+            Message::Mode(mode) => {
+                self.mode = mode;
+
+                Some(mode)
+            }
+            Message::Clear => None,
+        }
+    }
+
+    /// `output` is everything typed so far.
+    pub fn view(&mut self, output: &str) -> Element<Message> {
+        let modes = [
+            (TypewriterMode::Octal, "Octal"),
+            (TypewriterMode::DecimalFraction, "Decimal"),
+        ]
+        .iter()
+        .fold(Row::new().spacing(20), |row, &(mode, label)| {
+            row.push(
+                Radio::new(mode, label, Some(self.mode), Message::Mode)
+                    .text_size(text::SIZE_MEDIUM),
+            )
+        });
+
+        Column::new()
+            .padding(20)
+            .spacing(10)
+            .push(Text::new("TYPEWRITER").size(text::SIZE_LARGE))
+            .push(modes)
+            .push(
+                Scrollable::new(&mut self.scroll)
+                    .height(Length::Units(300))
+                    .push(Text::new(output).size(text::SIZE_MEDIUM)),
+            )
+            .push(
+                Button::new(
+                    &mut self.clear_button,
+                    Text::new("Clear").size(text::SIZE_MEDIUM),
+                )
+                .on_press(Message::Clear),
+            )
+            .into()
+    }
+}
+
+impl Default for TypewriterOutput {
+    fn default() -> Self {
+        Self::new()
+    }
+}